@@ -0,0 +1,282 @@
+// Repo cache entry: serialization and TTL decisions, independent of where it is stored
+use crate::api::store::AsyncStore;
+use crate::api::FetchError;
+use crate::models::{Repo, RepoKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const CACHE_TTL_MS: u64 = 15 * 60 * 1000; // 15 min
+
+/// Version written with every entry. Bump it and append to [`MIGRATIONS`] whenever the
+/// stored shape of [`CachedRepos`] or [`Repo`] changes.
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// Entries written before versioning was introduced carry no `version` field.
+const UNVERSIONED: u32 = 1;
+
+/// Upgrades a raw entry by one version, in place.
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` entry to version `n + 2`.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CachedRepos {
+    #[serde(default = "unversioned")]
+    pub version: u32,
+    pub repos: Vec<Repo>,
+    /// Unix milliseconds
+    pub fetched_at: u64,
+    /// Every page the list was assembled from, in order
+    #[serde(default)]
+    pub pages: Vec<CachedPage>,
+}
+
+/// One page of the repo list as last fetched: its validators and what it listed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CachedPage {
+    pub url: String,
+    /// `ETag`, sent back as `If-None-Match`
+    #[serde(default)]
+    pub etag: Option<String>,
+    /// `Last-Modified`, sent back as `If-Modified-Since`
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// The page's `rel="next"` link, followed again when the page comes back `304`
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Repos the page listed, looked up in [`CachedRepos::repos`] after a `304`
+    #[serde(default)]
+    pub keys: Vec<RepoKey>,
+}
+
+fn unversioned() -> u32 {
+    UNVERSIONED
+}
+
+impl CachedRepos {
+    /// True once the entry is older than [`CACHE_TTL_MS`] at `now_ms`.
+    pub fn is_stale(&self, now_ms: u64) -> bool {
+        now_ms.saturating_sub(self.fetched_at) > CACHE_TTL_MS
+    }
+
+    /// The cached page fetched from `url`, if any.
+    pub fn page(&self, url: &str) -> Option<&CachedPage> {
+        self.pages.iter().find(|p| p.url == url)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("cache entry serializes")
+    }
+
+    /// Parses an entry of any known version, migrating older ones to [`SCHEMA_VERSION`].
+    pub fn from_json(json: &str) -> Result<Self, FetchError> {
+        match Self::parse(json)? {
+            Some((cached, _)) => Ok(cached),
+            None => Err(FetchError::CacheCorrupt(
+                "written by a newer schema version".to_string(),
+            )),
+        }
+    }
+
+    /// Like [`CachedRepos::from_json`], also reporting whether a migration ran.
+    /// `Ok(None)` for an entry written with a newer schema than this build knows.
+    fn parse(json: &str) -> Result<Option<(Self, bool)>, FetchError> {
+        let corrupt = |e: String| FetchError::CacheCorrupt(e);
+        let mut value: Value = serde_json::from_str(json).map_err(|e| corrupt(e.to_string()))?;
+        let version = match value.get("version") {
+            None => UNVERSIONED,
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| corrupt(format!("bad version {}", v)))?,
+        };
+        if version == 0 {
+            return Err(corrupt(format!("unknown schema version {}", version)));
+        }
+        if version > SCHEMA_VERSION {
+            return Ok(None);
+        }
+        for migrate in &MIGRATIONS[(version - 1) as usize..] {
+            migrate(&mut value).map_err(corrupt)?;
+        }
+        value["version"] = SCHEMA_VERSION.into();
+        let cached = serde_json::from_value(value).map_err(|e| corrupt(e.to_string()))?;
+        Ok(Some((cached, version < SCHEMA_VERSION)))
+    }
+}
+
+/// v1 recorded `fetched_at` in seconds (or, briefly, unversioned milliseconds).
+fn migrate_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let fetched_at = value
+        .get("fetched_at")
+        .and_then(Value::as_u64)
+        .ok_or("missing fetched_at")?;
+    // Millisecond timestamps passed 10^12 in 2001; anything smaller is seconds
+    if fetched_at < 1_000_000_000_000 {
+        value["fetched_at"] = (fetched_at * 1000).into();
+    }
+    Ok(())
+}
+
+/// v2 repos predate topics, license, forks and the other extended fields. Dropping the
+/// validators makes the next refresh download them instead of getting a `304`.
+fn migrate_v2_to_v3(value: &mut Value) -> Result<(), String> {
+    let entry = value.as_object_mut().ok_or("entry is not an object")?;
+    entry.remove("etag");
+    entry.remove("last_modified");
+    Ok(())
+}
+
+/// v3 kept validators for the first page only, without the keys needed to rebuild it from
+/// the cached list. Dropping them costs one full download.
+fn migrate_v3_to_v4(value: &mut Value) -> Result<(), String> {
+    let entry = value.as_object_mut().ok_or("entry is not an object")?;
+    entry.remove("etag");
+    entry.remove("last_modified");
+    Ok(())
+}
+
+/// Reads the entry under `key`; `Ok(None)` when nothing is stored.
+///
+/// Older versions are migrated and written back. Entries that cannot be read are removed,
+/// so the next fetch starts clean instead of failing the same way until the TTL runs out.
+/// Entries from a newer schema are left alone and read as missing: a tab already running a
+/// newer deploy wrote them, and it should not lose its cache to this one.
+pub async fn read(store: &dyn AsyncStore, key: &str) -> Result<Option<CachedRepos>, FetchError> {
+    let Some(json) = store.load(key).await else {
+        return Ok(None);
+    };
+    match CachedRepos::parse(&json) {
+        Ok(None) => Ok(None),
+        Ok(Some((cached, migrated))) => {
+            if migrated {
+                write(store, key, &cached).await;
+            }
+            Ok(Some(cached))
+        }
+        Err(e) => {
+            store.delete(key).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn write(store: &dyn AsyncStore, key: &str, cached: &CachedRepos) {
+    store.save(key, &cached.to_json()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::store::{KeyValueStore, MemoryStore};
+    use futures::executor::block_on;
+
+    fn entry(fetched_at: u64) -> CachedRepos {
+        CachedRepos {
+            version: SCHEMA_VERSION,
+            repos: Vec::new(),
+            fetched_at,
+            pages: vec![CachedPage {
+                url: "https://example.com/repos".to_string(),
+                etag: Some("\"abc\"".to_string()),
+                ..CachedPage::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn stale_only_after_ttl() {
+        let cached = entry(1_000);
+        assert!(!cached.is_stale(1_000 + CACHE_TTL_MS));
+        assert!(cached.is_stale(1_000 + CACHE_TTL_MS + 1));
+        // Clock moved backwards: treat as fresh rather than underflow
+        assert!(!cached.is_stale(0));
+    }
+
+    #[test]
+    fn round_trips_through_store() {
+        let store = MemoryStore::new();
+        assert_eq!(block_on(read(&store, "k")), Ok(None));
+        block_on(write(&store, "k", &entry(42)));
+        assert_eq!(block_on(read(&store, "k")), Ok(Some(entry(42))));
+    }
+
+    #[test]
+    fn unversioned_entries_migrate_seconds_to_ms() {
+        let json = r#"{"repos":[],"fetched_at":1700000000}"#;
+        let cached = CachedRepos::from_json(json).unwrap();
+        assert_eq!(cached.version, SCHEMA_VERSION);
+        assert_eq!(cached.pages, []);
+        assert_eq!(cached.fetched_at, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn unversioned_ms_entries_are_kept() {
+        let json = r#"{"repos":[],"fetched_at":1700000000123}"#;
+        let cached = CachedRepos::from_json(json).unwrap();
+        assert_eq!(cached.fetched_at, 1_700_000_000_123);
+    }
+
+    #[test]
+    fn v2_entries_lose_validators() {
+        let json = r#"{"version":2,"repos":[{"name":"Senet","description":null,
+            "html_url":"https://github.com/azuree0/Senet","language":"Rust",
+            "stargazers_count":1,"updated_at":""}],
+            "fetched_at":5000,"etag":"\"abc\"","last_modified":"Wed"}"#;
+        let cached = CachedRepos::from_json(json).unwrap();
+        assert_eq!(cached.version, SCHEMA_VERSION);
+        assert_eq!(cached.fetched_at, 5_000);
+        assert_eq!(cached.pages, []);
+        assert_eq!(cached.repos[0].topics, Vec::<String>::new());
+        assert_eq!(cached.repos[0].license, None);
+    }
+
+    #[test]
+    fn v3_entries_lose_first_page_validators() {
+        let json = r#"{"version":3,"repos":[],"fetched_at":5000,"etag":"\"abc\"",
+            "last_modified":"Wed"}"#;
+        let cached = CachedRepos::from_json(json).unwrap();
+        assert_eq!(cached.version, SCHEMA_VERSION);
+        assert_eq!(cached.pages, []);
+    }
+
+    #[test]
+    fn migrated_entries_are_written_back() {
+        let store = MemoryStore::new();
+        store.set("k", r#"{"repos":[],"fetched_at":5}"#);
+        let cached = block_on(read(&store, "k")).unwrap().unwrap();
+        assert_eq!(cached.fetched_at, 5_000);
+        let stored: Value = serde_json::from_str(&store.get("k").unwrap()).unwrap();
+        assert_eq!(stored["version"], SCHEMA_VERSION);
+        assert_eq!(stored["fetched_at"], 5_000);
+    }
+
+    #[test]
+    fn garbage_is_reported_and_removed() {
+        let store = MemoryStore::new();
+        store.set("k", "{not json");
+        assert!(matches!(
+            block_on(read(&store, "k")),
+            Err(FetchError::CacheCorrupt(_))
+        ));
+        assert_eq!(store.get("k"), None);
+    }
+
+    #[test]
+    fn wrong_shape_is_removed() {
+        let store = MemoryStore::new();
+        store.set("k", r#"{"version":2,"repos":[{"name":1}],"fetched_at":5}"#);
+        assert!(block_on(read(&store, "k")).is_err());
+        assert_eq!(store.get("k"), None);
+    }
+
+    #[test]
+    fn future_versions_are_ignored() {
+        let store = MemoryStore::new();
+        let json = r#"{"version":99,"repos":[],"fetched_at":5}"#;
+        store.set("k", json);
+        assert_eq!(block_on(read(&store, "k")), Ok(None));
+        assert_eq!(store.get("k").as_deref(), Some(json));
+    }
+}
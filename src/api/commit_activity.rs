@@ -0,0 +1,132 @@
+// Weekly commit counts per repo for the card sparkline. GitHub computes repository
+// statistics in the background and answers `202 Accepted` until they are ready, so the
+// lookup polls, releasing its request slot between polls so other cards keep loading.
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::decode;
+use crate::api::FetchError;
+use crate::models::{CommitActivity, RepoKey, Timestamp};
+use serde::Deserialize;
+
+/// Statistics cover whole weeks; twice a day catches a new one soon enough.
+pub const COMMIT_ACTIVITY: DetailKind = DetailKind {
+    name: "commit_activity",
+    ttl_ms: 12 * 60 * 60 * 1000,
+};
+
+/// How long to keep asking while GitHub is still computing statistics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollPolicy {
+    /// Requests in total, including the first
+    pub max_polls: u32,
+    /// Wait before the second request, doubled for each one after
+    pub first_delay_ms: u32,
+    /// Upper bound for a single wait
+    pub max_delay_ms: u32,
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        // GitHub usually has statistics ready within a few seconds; about a minute in all
+        Self {
+            max_polls: 6,
+            first_delay_ms: 2_000,
+            max_delay_ms: 20_000,
+        }
+    }
+}
+
+impl PollPolicy {
+    /// Wait before poll number `poll` (1-based, so the first wait is before poll 2).
+    pub fn delay_ms(&self, poll: u32) -> u32 {
+        self.first_delay_ms
+            .saturating_mul(1u32 << poll.saturating_sub(2).min(16))
+            .min(self.max_delay_ms)
+    }
+}
+
+pub fn commit_activity_url(repo: &RepoKey) -> String {
+    format!(
+        "https://api.github.com/repos/{}/stats/commit_activity",
+        repo.slug()
+    )
+}
+
+/// One entry of the `commit_activity` response.
+#[derive(Debug, Deserialize)]
+struct WeekJson {
+    /// Unix seconds, start of the week
+    week: i64,
+    total: u32,
+}
+
+enum Poll {
+    Ready(CommitActivity),
+    Computing,
+}
+
+/// Commit counts per week for `repo`, from cache or GitHub.
+pub async fn fetch_commit_activity(repo: &RepoKey) -> Result<CommitActivity, FetchError> {
+    fetch_commit_activity_using(Backend::browser(), repo, &PollPolicy::default()).await
+}
+
+/// Like [`fetch_commit_activity`], over any [`Backend`] and polling as `policy` says.
+///
+/// Fails with `Http { status: 202 }` when the statistics are still not ready after the last
+/// poll; nothing is cached then, so the next lookup starts over.
+pub async fn fetch_commit_activity_using(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+    policy: &PollPolicy,
+) -> Result<CommitActivity, FetchError> {
+    details::cached_or_derive(backend, &COMMIT_ACTIVITY, repo, || async move {
+        let mut poll = 1;
+        loop {
+            if let Poll::Ready(activity) =
+                details::limited(backend, || request(backend, repo)).await?
+            {
+                return Ok(activity);
+            }
+            if poll >= policy.max_polls {
+                return Err(FetchError::Http { status: 202 });
+            }
+            poll += 1;
+            // Waits without a permit, so the other cards' lookups go ahead meanwhile
+            backend.clock.sleep(policy.delay_ms(poll)).await;
+        }
+    })
+    .await
+}
+
+async fn request(backend: Backend<'_>, repo: &RepoKey) -> Result<Poll, FetchError> {
+    let request = HttpRequest::get(&commit_activity_url(repo))
+        .header("Accept", "application/vnd.github.v3+json");
+    let response = github::send(backend, request, None).await?;
+    match response.status {
+        202 => Ok(Poll::Computing),
+        // Empty repository
+        204 => Ok(Poll::Ready(CommitActivity::default())),
+        _ => {
+            let weeks: Vec<WeekJson> = decode(&response.body)?;
+            Ok(Poll::Ready(CommitActivity {
+                first_week: weeks
+                    .first()
+                    .map(|w| Timestamp::from_unix_ms(w.week * 1000)),
+                weekly: weeks.iter().map(|w| w.total).collect(),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_delays_double_up_to_the_cap() {
+        let policy = PollPolicy::default();
+        let delays: Vec<_> = (2..=6).map(|poll| policy.delay_ms(poll)).collect();
+        assert_eq!(delays, [2_000, 4_000, 8_000, 16_000, 20_000]);
+    }
+}
@@ -0,0 +1,295 @@
+// Per-repo detail lookups (languages, …): each cached under its own key and TTL, fetched
+// lazily, a few at a time, and only while the rate limit has quota to spare. Profile-wide
+// extras (the activity calendar) go through the same path, keyed by user.
+use crate::api::github::Backend;
+use crate::api::rate_limit;
+use crate::api::FetchError;
+use crate::models::RepoKey;
+use futures::channel::oneshot;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+
+/// Detail requests in flight at once; the rest wait their turn.
+const MAX_CONCURRENT: usize = 2;
+
+/// Cache record for one detail of one repo.
+#[derive(Debug, Deserialize, Serialize)]
+struct CachedDetail<T> {
+    value: T,
+    /// Unix milliseconds
+    fetched_at: u64,
+}
+
+/// What one kind of detail is called in cache keys, and how long it stays fresh.
+pub struct DetailKind {
+    pub name: &'static str,
+    pub ttl_ms: u64,
+}
+
+impl DetailKind {
+    /// e.g. `portfolio_languages:github:azuree0/senet` for a [`RepoKey`]
+    pub fn cache_key(&self, subject: &impl fmt::Display) -> String {
+        format!("portfolio_{}:{}", self.name, subject)
+    }
+}
+
+/// Returns the cached detail while it is fresh; otherwise runs `fetch` and caches the result.
+///
+/// When fetching is not possible (quota reserved for the repo list) or fails, an expired
+/// cache entry is served instead of the error.
+pub async fn cached_or_fetch<T, F, Fut>(
+    backend: Backend<'_>,
+    kind: &DetailKind,
+    repo: &RepoKey,
+    fetch: F,
+) -> Result<T, FetchError>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    cached_or_refresh(backend, kind, repo, |_| fetch()).await
+}
+
+/// Like [`cached_or_fetch`], but `fetch` is handed the expired value, if any, so it can
+/// reuse the parts that have not changed.
+pub async fn cached_or_refresh<T, F, Fut>(
+    backend: Backend<'_>,
+    kind: &DetailKind,
+    repo: &RepoKey,
+    fetch: F,
+) -> Result<T, FetchError>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    F: FnOnce(Option<T>) -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    cached(backend, kind, repo, |previous| {
+        limited(backend, || fetch(previous))
+    })
+    .await
+}
+
+/// Like [`cached_or_fetch`], for something other than a repo: `subject` names it in the
+/// cache key, e.g. a user name.
+pub async fn cached_or_fetch_for<T, F, Fut>(
+    backend: Backend<'_>,
+    kind: &DetailKind,
+    subject: &str,
+    fetch: F,
+) -> Result<T, FetchError>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    cached(backend, kind, &subject, |_| limited(backend, fetch)).await
+}
+
+/// Caches a detail worked out from other lookups. `derive` makes its requests through
+/// [`limited`] or the other detail functions, so it holds no permit of its own (waiting on a
+/// second permit while holding one could stall every slot).
+pub async fn cached_or_derive<T, F, Fut>(
+    backend: Backend<'_>,
+    kind: &DetailKind,
+    repo: &RepoKey,
+    derive: F,
+) -> Result<T, FetchError>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    cached(backend, kind, repo, |_| derive()).await
+}
+
+/// Runs one optional request once a permit is free, unless the rate limit has no quota to
+/// spare for it.
+pub async fn limited<T, F, Fut>(backend: Backend<'_>, fetch: F) -> Result<T, FetchError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let _permit = acquire().await;
+    // Checked after the wait: earlier requests in the queue may have used up the quota
+    if !rate_limit::allows_optional(backend.store, backend.clock) {
+        return Err(FetchError::RateLimited {
+            reset_at: rate_limit::load(backend.store).and_then(|r| r.blocked_until.or(r.reset_at)),
+        });
+    }
+    fetch().await
+}
+
+async fn cached<T, F, Fut>(
+    backend: Backend<'_>,
+    kind: &DetailKind,
+    subject: &impl fmt::Display,
+    fetch: F,
+) -> Result<T, FetchError>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    F: FnOnce(Option<T>) -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let key = kind.cache_key(subject);
+    let cached = match backend.cache.load(&key).await {
+        Some(json) => match serde_json::from_str::<CachedDetail<T>>(&json) {
+            Ok(cached) => Some(cached),
+            Err(_) => {
+                backend.cache.delete(&key).await;
+                None
+            }
+        },
+        None => None,
+    };
+    let now = backend.clock.now_ms();
+    if let Some(cached) = cached {
+        if now.saturating_sub(cached.fetched_at) <= kind.ttl_ms {
+            return Ok(cached.value);
+        }
+        let previous = cached.value.clone();
+        return match fetch_fresh(backend, &key, || fetch(Some(previous))).await {
+            Ok(value) => Ok(value),
+            Err(_) => Ok(cached.value),
+        };
+    }
+    fetch_fresh(backend, &key, || fetch(None)).await
+}
+
+async fn fetch_fresh<T, F, Fut>(backend: Backend<'_>, key: &str, fetch: F) -> Result<T, FetchError>
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let value = fetch().await?;
+    let cached = CachedDetail {
+        value,
+        fetched_at: backend.clock.now_ms(),
+    };
+    if let Ok(json) = serde_json::to_string(&cached) {
+        backend.cache.save(key, &json).await;
+    }
+    Ok(cached.value)
+}
+
+#[derive(Default)]
+struct Limiter {
+    active: usize,
+    waiting: VecDeque<oneshot::Sender<()>>,
+}
+
+thread_local! {
+    static LIMITER: RefCell<Limiter> = RefCell::new(Limiter::default());
+}
+
+/// A slot among the [`MAX_CONCURRENT`] detail requests; passed on to the next waiter on drop.
+struct Permit;
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        LIMITER.with(|limiter| {
+            let mut limiter = limiter.borrow_mut();
+            // Waiters whose card went away have dropped their receiver; skip them
+            while let Some(next) = limiter.waiting.pop_front() {
+                if next.send(()).is_ok() {
+                    return;
+                }
+            }
+            limiter.active -= 1;
+        });
+    }
+}
+
+/// A place in the queue. Dropped after being handed a permit but before running (the
+/// card went away in between), it passes the permit on instead of leaking it.
+struct Turn(oneshot::Receiver<()>);
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        if let Ok(Some(())) = self.0.try_recv() {
+            drop(Permit);
+        }
+    }
+}
+
+async fn acquire() -> Permit {
+    let wait = LIMITER.with(|limiter| {
+        let mut limiter = limiter.borrow_mut();
+        if limiter.active < MAX_CONCURRENT {
+            limiter.active += 1;
+            return None;
+        }
+        let (tx, rx) = oneshot::channel();
+        limiter.waiting.push_back(tx);
+        Some(Turn(rx))
+    });
+    if let Some(mut turn) = wait {
+        // The sender is only dropped unsent when the queue is torn down with the page
+        let _ = (&mut turn.0).await;
+    }
+    Permit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::task::noop_waker_ref;
+    use std::pin::pin;
+    use std::task::{Context, Poll};
+
+    fn active() -> usize {
+        LIMITER.with(|l| l.borrow().active)
+    }
+
+    #[test]
+    fn permits_are_handed_to_waiters_in_order() {
+        let first = block_on(acquire());
+        let second = block_on(acquire());
+        let mut third = pin!(acquire());
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(third.as_mut().poll(&mut cx).is_pending());
+
+        drop(first);
+        let Poll::Ready(third) = third.as_mut().poll(&mut cx) else {
+            panic!("third request should run once the first finishes");
+        };
+        assert_eq!(active(), 2);
+        drop(second);
+        drop(third);
+        assert_eq!(active(), 0);
+    }
+
+    #[test]
+    fn abandoned_waiters_are_skipped() {
+        let first = block_on(acquire());
+        let _second = block_on(acquire());
+        {
+            let mut gone = pin!(acquire());
+            let mut cx = Context::from_waker(noop_waker_ref());
+            assert!(gone.as_mut().poll(&mut cx).is_pending());
+        }
+        drop(first);
+        assert_eq!(active(), 1);
+    }
+
+    #[test]
+    fn permit_handed_to_a_waiter_that_never_ran_is_passed_on() {
+        let first = block_on(acquire());
+        let second = block_on(acquire());
+        let mut cx = Context::from_waker(noop_waker_ref());
+        {
+            let mut gone = pin!(acquire());
+            assert!(gone.as_mut().poll(&mut cx).is_pending());
+            drop(first);
+        }
+        assert_eq!(active(), 1);
+        drop(second);
+        assert_eq!(active(), 0);
+    }
+}
@@ -0,0 +1,64 @@
+// Error and provenance types for the GitHub API layer
+use crate::models::Repo;
+use std::fmt;
+
+/// Why a fetch from GitHub did not produce fresh data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FetchError {
+    /// Request never completed (offline, DNS, CORS)
+    Network(String),
+    /// Non-success HTTP status
+    Http { status: u16 },
+    /// GitHub asked us to back off until `reset_at` (Unix seconds)
+    RateLimited { reset_at: Option<u64> },
+    /// Body did not match the expected shape
+    Decode { path: String, message: String },
+    /// Stored cache entry could not be read
+    CacheCorrupt(String),
+    /// Superseded by a newer refresh and cancelled
+    Aborted,
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(e) => write!(f, "Network error: {}", e),
+            Self::Http { status } => write!(f, "GitHub API error: {}", status),
+            Self::RateLimited { reset_at: Some(t) } => {
+                write!(f, "GitHub API rate limited until {}", t)
+            }
+            Self::RateLimited { reset_at: None } => write!(f, "GitHub API rate limited"),
+            Self::Decode { path, message } => write!(f, "Parse error at {}: {}", path, message),
+            Self::CacheCorrupt(e) => write!(f, "Cache corrupt: {}", e),
+            Self::Aborted => write!(f, "Request aborted"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Where the repos being shown came from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataSource {
+    /// Downloaded from GitHub just now
+    Fresh,
+    /// Cached copy confirmed unchanged by `304 Not Modified`
+    Revalidated,
+    /// Cached copy still within its TTL; network not touched. `fetched_at` is Unix ms.
+    Cached { fetched_at: u64 },
+    /// Cached copy past its TTL, shown while it is being revalidated. `fetched_at` is Unix ms.
+    Expired { fetched_at: u64 },
+    /// Expired cached copy, served because the network fetch failed. `fetched_at` is Unix ms.
+    StaleCache { fetched_at: u64, error: FetchError },
+    /// Hard-coded `static_fallback` list
+    StaticFallback { error: FetchError },
+}
+
+/// Repos together with their provenance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchOutcome {
+    pub repos: Vec<Repo>,
+    pub source: DataSource,
+    /// Why the stored cache was discarded, when it could not be read
+    pub cache_error: Option<FetchError>,
+}
@@ -0,0 +1,65 @@
+// The profile's public events, bucketed by day for the activity calendar below the hero
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::{decode, next_page_url};
+use crate::api::FetchError;
+use crate::models::activity::{self, Activity, EventKind};
+use crate::models::timestamp::{self, Timestamp};
+use serde::Deserialize;
+
+pub const USER: &str = "azuree0";
+pub const EVENTS_URL: &str = "https://api.github.com/users/azuree0/events/public?per_page=100";
+
+/// How far back GitHub's public event feed reaches.
+pub const FEED_DAYS: u32 = 90;
+/// GitHub serves at most 300 events (and only from the last [`FEED_DAYS`]), so three full pages.
+const MAX_PAGES: usize = 3;
+
+/// New events show up within minutes; an hour keeps the calendar current enough.
+pub const ACTIVITY: DetailKind = DetailKind {
+    name: "activity",
+    ttl_ms: 60 * 60 * 1000,
+};
+
+/// One entry of `GET /users/{user}/events/public`; only what the calendar needs.
+#[derive(Debug, Deserialize)]
+struct EventJson {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    created_at: Option<Timestamp>,
+}
+
+/// Public activity over the last [`FEED_DAYS`], all the feed serves, from cache or GitHub.
+pub async fn fetch_activity() -> Result<Activity, FetchError> {
+    fetch_activity_using(Backend::browser()).await
+}
+
+/// Like [`fetch_activity`], over any [`Backend`].
+pub async fn fetch_activity_using(backend: Backend<'_>) -> Result<Activity, FetchError> {
+    details::cached_or_fetch_for(backend, &ACTIVITY, USER, || async {
+        let mut events = Vec::new();
+        let mut next = Some(EVENTS_URL.to_string());
+        for _ in 0..MAX_PAGES {
+            let Some(url) = next.take() else {
+                break;
+            };
+            let request = HttpRequest::get(&url).header("Accept", "application/vnd.github.v3+json");
+            let response = github::send(backend, request, None).await?;
+            let page: Vec<EventJson> = decode(&response.body)?;
+            events.extend(page);
+            next = response.header("link").and_then(next_page_url);
+        }
+
+        let now = Timestamp::from_unix_ms(backend.clock.now_ms() as i64);
+        let since = activity::day_number(now) - (i64::from(FEED_DAYS) - 1);
+        Ok(Activity::from_events(
+            events
+                .into_iter()
+                .filter_map(|event| Some((event.created_at?, EventKind::from_type(&event.kind)))),
+            since,
+        ))
+    })
+    .await
+}
@@ -1,17 +1,21 @@
-use crate::models::Repo;
-use gloo_storage::{LocalStorage, Storage};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::api::cache::{self, CachedPage, CachedRepos};
+use crate::api::clock::{Clock, SystemClock};
+use crate::api::http::{GlooClient, HttpClient, HttpRequest, HttpResponse};
+use crate::api::idb;
+use crate::api::parse::{decode, next_page_url};
+use crate::api::rate_limit::{self, RateLimit};
+use crate::api::retry::{self, RetryPolicy};
+use crate::api::store::{AsyncStore, KeyValueStore, LocalStore};
+use crate::api::{inflight, tabs};
+use crate::api::{DataSource, FetchError, FetchOutcome};
+use crate::models::{Repo, RepoDiff, RepoKey};
+use std::collections::{HashMap, HashSet};
+use web_sys::AbortSignal;
 
-const CACHE_KEY: &str = "portfolio_repos";
-const CACHE_TTL_SECS: u64 = 900; // 15 min
-
-#[derive(Serialize, Deserialize)]
-struct CachedRepos {
-    repos: Vec<Repo>,
-    fetched_at: u64,
-}
+pub const CACHE_KEY: &str = "portfolio_repos";
+pub const REPOS_URL: &str = "https://api.github.com/users/azuree0/repos?sort=updated&per_page=100";
+/// Default cap on repos merged across all pages.
+pub const DEFAULT_REPO_LIMIT: usize = 500;
 
 /// Static fallback repos when API fails (azuree0's known repos)
 pub fn static_fallback() -> Vec<Repo> {
@@ -23,8 +27,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Senet", base),
             language: Some("Rust".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/b12746e5-fb64-41a4-b343-5ec77166cff6".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Latrones".to_string(),
@@ -32,8 +35,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Latrones", base),
             language: Some("Rust".to_string()),
             stargazers_count: 0,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/182fd35b-a924-4749-8f37-9f48060ec49f".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Chaturanga".to_string(),
@@ -41,8 +43,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Chaturanga", base),
             language: Some("Rust".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/ebd040e8-8939-4ad7-bc43-e655a4ba6582".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Go".to_string(),
@@ -50,8 +51,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Go", base),
             language: Some("Rust".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/f52cbdc4-afe9-4169-8a03-ed025b6a834a".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Game-of-Ur".to_string(),
@@ -59,8 +59,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Game-of-Ur", base),
             language: Some("Rust".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/fe00622d-3483-47b2-b9ff-b00a17f4c159".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Mehen".to_string(),
@@ -68,8 +67,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Mehen", base),
             language: Some("Rust".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/b9a324c1-822d-49ed-b88e-13fbc2b17f04".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Nard".to_string(),
@@ -77,8 +75,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Nard", base),
             language: Some("Rust".to_string()),
             stargazers_count: 0,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/9cdc289f-cd3d-433f-af37-d508c45c7179".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Silent-data-corruption".to_string(),
@@ -86,8 +83,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Silent-data-corruption", base),
             language: Some("C++".to_string()),
             stargazers_count: 0,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/1db52073-faf9-4e6d-895c-36c66dc1625d".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Rubik".to_string(),
@@ -95,8 +91,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Rubik", base),
             language: Some("C++".to_string()),
             stargazers_count: 1,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/5de4e3d7-b660-4ea4-a513-aca077b695cf".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Liquid".to_string(),
@@ -104,8 +99,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Liquid", base),
             language: Some("Liquid".to_string()),
             stargazers_count: 0,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/ac0f0af2-e95e-4591-b848-e30c89675822".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Bria-ai".to_string(),
@@ -113,8 +107,7 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Bria-ai", base),
             language: Some("Python".to_string()),
             stargazers_count: 0,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/ed5d7f08-27a7-40fb-a93a-6f2b4b89cab4".to_string()),
+            ..Repo::default()
         },
         Repo {
             name: "Aerospace".to_string(),
@@ -122,97 +115,372 @@ pub fn static_fallback() -> Vec<Repo> {
             html_url: format!("{}/Aerospace", base),
             language: Some("Python".to_string()),
             stargazers_count: 2,
-            updated_at: String::new(),
-            screenshot: Some("https://github.com/user-attachments/assets/86f9cac2-334b-4cf7-b374-d82dba4bd186".to_string()),
+            ..Repo::default()
         },
     ]
 }
 
-pub async fn fetch_repos() -> Result<Vec<Repo>, String> {
-    // Try cache first (instant load if valid)
-    if let Ok(cached) = get_cached() {
-        if !is_stale(cached.fetched_at) {
-            return Ok(cached.repos);
+/// Knobs for a single repo fetch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchOptions {
+    /// Cap on repos merged across all pages
+    pub limit: usize,
+    /// Go to the network even if the cache is within its TTL (still sends validators)
+    pub bypass_ttl: bool,
+    /// Retries for transient failures of each page request
+    pub retry: RetryPolicy,
+}
+
+impl FetchOptions {
+    /// True if a fetch made with `self` also answers a request for `other`: the same knobs,
+    /// and at least as ready to skip the cache.
+    pub fn covers(&self, other: &FetchOptions) -> bool {
+        self.limit == other.limit
+            && self.retry == other.retry
+            && (self.bypass_ttl || !other.bypass_ttl)
+    }
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_REPO_LIMIT,
+            bypass_ttl: false,
+            retry: RetryPolicy::default(),
         }
     }
+}
 
-    let url = "https://api.github.com/users/azuree0/repos?sort=updated&per_page=100";
-    let response = match gloo_net::http::Request::get(url)
-        .header("Accept", "application/vnd.github.v3+json")
-        .send()
-        .await
-    {
-        Ok(r) => r,
-        Err(e) => {
-            // Network/CORS failure: try cache, then static fallback
-            if let Ok(cached) = get_cached() {
-                return Ok(cached.repos);
-            }
-            return Err(format!("Network error: {}. Using fallback.", e));
+/// Everything the fetch path touches besides pure logic: network, persistence and time.
+#[derive(Clone, Copy)]
+pub struct Backend<'a> {
+    pub http: &'a dyn HttpClient,
+    /// Small records: rate limit state
+    pub store: &'a dyn KeyValueStore,
+    /// The repo list cache
+    pub cache: &'a dyn AsyncStore,
+    pub clock: &'a dyn Clock,
+}
+
+impl Backend<'static> {
+    /// gloo-net, localStorage, the IndexedDB cache and the system clock.
+    pub fn browser() -> Self {
+        Self {
+            http: &GlooClient,
+            store: &LocalStore,
+            cache: idb::browser_cache(),
+            clock: &SystemClock,
         }
-    };
+    }
+}
+
+pub async fn fetch_repos() -> Result<FetchOutcome, FetchError> {
+    fetch_repos_with(&FetchOptions::default()).await
+}
 
-    if !response.ok() {
-        if let Ok(cached) = get_cached() {
-            return Ok(cached.repos);
+/// Fetches repos following `Link: rel="next"` pagination, stopping after `options.limit` repos.
+///
+/// Fails only when the network fetch failed and there is no cache to fall back to.
+pub async fn fetch_repos_with(options: &FetchOptions) -> Result<FetchOutcome, FetchError> {
+    fetch_repos_using(Backend::browser(), options, None).await
+}
+
+/// Like [`fetch_repos_with`], over any [`Backend`], with every page request tied to `signal`.
+pub async fn fetch_repos_using(
+    backend: Backend<'_>,
+    options: &FetchOptions,
+    signal: Option<&AbortSignal>,
+) -> Result<FetchOutcome, FetchError> {
+    let limit = options.limit;
+    // Try cache first (instant load if valid)
+    let now = backend.clock.now_ms();
+    let (cached, cache_error) = read_cache(backend).await;
+    if let Some(ref cached) = cached {
+        if !options.bypass_ttl && !cached.is_stale(now) {
+            return Ok(FetchOutcome {
+                repos: cached.repos.clone(),
+                source: DataSource::Cached {
+                    fetched_at: cached.fetched_at,
+                },
+                cache_error: None,
+            });
         }
-        return Err(format!("GitHub API error: {}", response.status()));
     }
 
-    let mut repos: Vec<Repo> = match response.json().await {
-        Ok(r) => r,
-        Err(e) => {
-            if let Ok(cached) = get_cached() {
-                return Ok(cached.repos);
-            }
-            return Err(format!("Parse error: {}", e));
+    // GitHub asked us to back off: serve whatever we have without touching the network
+    if rate_limit::is_blocked(backend.store, backend.clock) {
+        let error = FetchError::RateLimited {
+            reset_at: rate_limit::load(backend.store).and_then(|r| r.blocked_until),
+        };
+        return stale_or(cached, error);
+    }
+
+    // Every page is revalidated on its own: with `sort=updated` a changed repo moves to the
+    // top, but a repo deleted or made private further down only changes its own page
+    let mut repos: Vec<Repo> = Vec::new();
+    let mut seen: HashSet<RepoKey> = HashSet::new();
+    let mut pages: Vec<CachedPage> = Vec::new();
+    let mut all_not_modified = true;
+    let cached_by_key: HashMap<RepoKey, &Repo> = cached
+        .iter()
+        .flat_map(|c| &c.repos)
+        .map(|r| (r.key(), r))
+        .collect();
+    let mut next = Some(REPOS_URL.to_string());
+    while let Some(url) = next.take() {
+        if repos.len() >= limit {
+            break;
         }
+        let previous = cached.as_ref().and_then(|c| c.page(&url));
+        let page = retry::with_retry(&options.retry, backend, signal, || {
+            fetch_page(backend, &url, previous, signal)
+        })
+        .await;
+        let (listed, page) = match page {
+            Ok(Page::NotModified) => {
+                // Validators are only sent for pages in the cache
+                let Some(previous) = previous else {
+                    return Err(FetchError::Http { status: 304 });
+                };
+                let listed = previous
+                    .keys
+                    .iter()
+                    .filter_map(|key| cached_by_key.get(key).map(|r| (*r).clone()))
+                    .collect();
+                (listed, previous.clone())
+            }
+            Ok(Page::Fresh { repos, page }) => {
+                all_not_modified = false;
+                (repos, page)
+            }
+            Err(_) if signal.is_some_and(AbortSignal::aborted) => return Err(FetchError::Aborted),
+            // Partial results are not cached: fall back to cache, then static fallback
+            Err(e) => return stale_or(cached, e),
+        };
+        // A repo updated mid-walk moves to the front and shifts the rest back one
+        // slot, so the next page can repeat the previous page's last entry
+        repos.extend(listed.into_iter().filter(|r| seen.insert(r.key())));
+        next = page.next.clone();
+        pages.push(page);
+    }
+    repos.truncate(limit);
+
+    set_cache(backend, &repos, pages).await;
+    let source = if all_not_modified {
+        DataSource::Revalidated
+    } else {
+        DataSource::Fresh
     };
+    Ok(FetchOutcome {
+        repos,
+        source,
+        cache_error,
+    })
+}
 
-    // Merge screenshots from static fallback (API does not return them)
-    let fallback = static_fallback();
-    let screenshot_map: HashMap<String, String> = fallback
-        .into_iter()
-        .filter_map(|r| r.screenshot.map(|s| (r.name, s)))
-        .collect();
-    for repo in &mut repos {
-        if let Some(screenshot) = screenshot_map.get(&repo.name) {
-            repo.screenshot = Some(screenshot.clone());
+/// The cached entry, or `None` and why it was discarded when it could not be read.
+async fn read_cache(backend: Backend<'_>) -> (Option<CachedRepos>, Option<FetchError>) {
+    match cache::read(backend.cache, CACHE_KEY).await {
+        Ok(cached) => (cached, None),
+        Err(e) => (None, Some(e)),
+    }
+}
+
+/// Serves an expired cache after `error`, or fails with `error` if there is none.
+fn stale_or(cached: Option<CachedRepos>, error: FetchError) -> Result<FetchOutcome, FetchError> {
+    match cached {
+        Some(cached) => Ok(FetchOutcome {
+            repos: cached.repos,
+            source: DataSource::StaleCache {
+                fetched_at: cached.fetched_at,
+                error,
+            },
+            cache_error: None,
+        }),
+        None => Err(error),
+    }
+}
+
+/// Result of fetching one page of repos.
+enum Page {
+    /// Server answered `304 Not Modified` to a conditional request.
+    NotModified,
+    Fresh {
+        repos: Vec<Repo>,
+        page: CachedPage,
+    },
+}
+
+/// Fetches a single page of repos, sending `If-None-Match` / `If-Modified-Since` with the
+/// validators of the `previous` copy when given.
+async fn fetch_page(
+    backend: Backend<'_>,
+    url: &str,
+    previous: Option<&CachedPage>,
+    signal: Option<&AbortSignal>,
+) -> Result<Page, FetchError> {
+    let mut request = HttpRequest::get(url).header("Accept", "application/vnd.github.v3+json");
+    if let Some(previous) = previous {
+        if let Some(ref etag) = previous.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(ref last_modified) = previous.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
         }
     }
+    let response = send(backend, request, signal).await?;
+    if response.status == 304 {
+        return Ok(Page::NotModified);
+    }
+
+    let repos: Vec<Repo> = decode(&response.body)?;
+    let page = CachedPage {
+        url: url.to_string(),
+        etag: response.header("etag").map(str::to_string),
+        last_modified: response.header("last-modified").map(str::to_string),
+        next: response.header("link").and_then(next_page_url),
+        keys: repos.iter().map(Repo::key).collect(),
+    };
+    Ok(Page::Fresh { repos, page })
+}
 
-    set_cache(&repos);
-    Ok(repos)
+/// Sends a GitHub API request, recording the rate-limit headers of whatever comes back.
+///
+/// Returns successful and `304 Not Modified` responses; other statuses become errors.
+pub(crate) async fn send(
+    backend: Backend<'_>,
+    request: HttpRequest,
+    signal: Option<&AbortSignal>,
+) -> Result<HttpResponse, FetchError> {
+    let response = backend.http.get(request, signal).await?;
+
+    let now = backend.clock.now_secs();
+    let rate_limit = RateLimit::from_response(&response, now);
+    rate_limit::store(backend.store, &rate_limit);
+
+    if response.ok() || response.status == 304 {
+        return Ok(response);
+    }
+    if rate_limit.blocks_at(now) {
+        return Err(FetchError::RateLimited {
+            reset_at: rate_limit.blocked_until,
+        });
+    }
+    Err(FetchError::Http {
+        status: response.status,
+    })
 }
 
 /// Returns repos from cache, API, or static fallback. Never fails.
-pub async fn fetch_repos_with_fallback() -> Vec<Repo> {
-    match fetch_repos().await {
-        Ok(repos) => repos,
-        Err(_) => static_fallback(),
+pub async fn fetch_repos_with_fallback(options: &FetchOptions) -> FetchOutcome {
+    or_static_fallback(fetch_repos_with(options).await)
+}
+
+pub(crate) fn or_static_fallback(result: Result<FetchOutcome, FetchError>) -> FetchOutcome {
+    match result {
+        Ok(outcome) => outcome,
+        Err(error) => FetchOutcome {
+            repos: static_fallback(),
+            source: DataSource::StaticFallback { error },
+            cache_error: None,
+        },
     }
 }
 
-fn get_cached() -> Result<CachedRepos, ()> {
-    LocalStorage::get(CACHE_KEY).map_err(|_| ())
+/// How repos another tab wrote to the cache are reported: a tab only announces a list it
+/// just downloaded or revalidated.
+pub const BROADCAST_SOURCE: DataSource = DataSource::Fresh;
+
+/// One step of a stale-while-revalidate fetch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwrUpdate {
+    pub source: DataSource,
+    /// New repo list, or `None` when it is unchanged since the previous update
+    pub repos: Option<Vec<Repo>>,
+    /// Why the stored cache was discarded, when it could not be read
+    pub cache_error: Option<FetchError>,
 }
 
-fn set_cache(repos: &[Repo]) {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    let cached = CachedRepos {
-        repos: repos.to_vec(),
-        fetched_at: now,
+/// Stale-while-revalidate: reports cached repos right away, then revalidates in the
+/// background if the cache is expired (or `options.bypass_ttl` is set).
+///
+/// `on_update` is called once for the cached copy and once after revalidation; the second
+/// call only carries repos when the list actually changed.
+pub async fn fetch_repos_swr(options: &FetchOptions, on_update: impl Fn(SwrUpdate)) {
+    let backend = Backend::browser();
+    let (cached, cache_error) = read_cache(backend).await;
+    if let Some(ref cached) = cached {
+        let fetched_at = cached.fetched_at;
+        let stale = cached.is_stale(backend.clock.now_ms());
+        on_update(SwrUpdate {
+            source: if stale {
+                DataSource::Expired { fetched_at }
+            } else {
+                DataSource::Cached { fetched_at }
+            },
+            repos: Some(cached.repos.clone()),
+            cache_error: None,
+        });
+        if !options.bypass_ttl && !stale {
+            return;
+        }
+    }
+
+    let Some(_lock) = tabs::try_acquire_lock() else {
+        // Another tab is already fetching: wait until it writes the cache or gives up
+        // rather than returning at once, so a manual refresh here still ends with an update
+        tabs::next_update().await;
+        if let Some(latest) = cache::read(backend.cache, CACHE_KEY).await.unwrap_or(None) {
+            if cached.as_ref().map(|c| c.fetched_at) != Some(latest.fetched_at) {
+                on_update(SwrUpdate {
+                    source: BROADCAST_SOURCE,
+                    repos: changed(cached.map(|c| c.repos), latest.repos),
+                    cache_error,
+                });
+            }
+        }
+        return;
+    };
+    // A manual refresh (`bypass_ttl`) supersedes a scheduled one still in flight; a
+    // scheduled refresh joins whatever is in flight
+    let Some(outcome) = inflight::fetch_latest(options.clone()).await else {
+        // A newer refresh superseded this one; its caller reports instead
+        return;
     };
-    let _ = LocalStorage::set(CACHE_KEY, cached);
+    if matches!(outcome.source, DataSource::Fresh | DataSource::Revalidated) {
+        tabs::announce_update();
+    }
+    on_update(SwrUpdate {
+        source: outcome.source,
+        repos: changed(cached.map(|c| c.repos), outcome.repos),
+        // This read deleted a corrupt entry, so the fetch itself found none
+        cache_error: cache_error.or(outcome.cache_error),
+    });
 }
 
-fn is_stale(fetched_at: u64) -> bool {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    now.saturating_sub(fetched_at) > CACHE_TTL_SECS
+/// The new list, or `None` when it matches `previous`.
+fn changed(previous: Option<Vec<Repo>>, repos: Vec<Repo>) -> Option<Vec<Repo>> {
+    match previous {
+        Some(previous) if RepoDiff::between(&previous, &repos).is_empty() => None,
+        _ => Some(repos),
+    }
+}
+
+/// Repos written to the shared cache by this or another tab, without touching the network.
+pub async fn cached_repos() -> Option<Vec<Repo>> {
+    cache::read(idb::browser_cache(), CACHE_KEY)
+        .await
+        .ok()
+        .flatten()
+        .map(|c| c.repos)
+}
+
+async fn set_cache(backend: Backend<'_>, repos: &[Repo], pages: Vec<CachedPage>) {
+    let cached = CachedRepos {
+        version: cache::SCHEMA_VERSION,
+        repos: repos.to_vec(),
+        fetched_at: backend.clock.now_ms(),
+        pages,
+    };
+    cache::write(backend.cache, CACHE_KEY, &cached).await;
 }
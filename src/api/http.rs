@@ -0,0 +1,193 @@
+// HTTP seam for the GitHub API layer: gloo-net in the browser, recorded fixtures in tests
+use crate::api::FetchError;
+use futures::future::{FutureExt, LocalBoxFuture};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use web_sys::AbortSignal;
+
+/// A GET request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    pub fn get(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Case-insensitive header lookup.
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A fully buffered response.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Case-insensitive header lookup.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Sends GET requests. Implementations resolve transport failures to [`FetchError::Network`].
+pub trait HttpClient {
+    fn get<'a>(
+        &'a self,
+        request: HttpRequest,
+        signal: Option<&'a AbortSignal>,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>>;
+}
+
+/// Browser `fetch` via gloo-net.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlooClient;
+
+impl HttpClient for GlooClient {
+    fn get<'a>(
+        &'a self,
+        request: HttpRequest,
+        signal: Option<&'a AbortSignal>,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>> {
+        async move {
+            let mut builder = gloo_net::http::Request::get(&request.url).abort_signal(signal);
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| FetchError::Network(e.to_string()))?;
+            let status = response.status();
+            let headers = response.headers().entries().collect();
+            // 304 has no body; reading it anyway is harmless
+            let body = response
+                .text()
+                .await
+                .map_err(|e| FetchError::Network(e.to_string()))?;
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+        .boxed_local()
+    }
+}
+
+/// One recorded exchange for [`FixtureClient`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fixture {
+    Response(HttpResponse),
+    /// Transport failure (offline, CORS)
+    NetworkError(String),
+    /// Request never completed
+    Timeout,
+}
+
+/// In-memory client replaying fixtures per URL in order, recording every request it sees.
+///
+/// The last fixture for a URL repeats once the queue is drained; unknown URLs fail with a
+/// network error.
+#[derive(Default)]
+pub struct FixtureClient {
+    fixtures: RefCell<HashMap<String, VecDeque<Fixture>>>,
+    requests: RefCell<Vec<HttpRequest>>,
+}
+
+impl FixtureClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `fixture` as the next reply for `url`.
+    pub fn on(self, url: &str, fixture: Fixture) -> Self {
+        self.fixtures
+            .borrow_mut()
+            .entry(url.to_string())
+            .or_default()
+            .push_back(fixture);
+        self
+    }
+
+    /// Queues a JSON body with the given status.
+    pub fn on_json(self, url: &str, status: u16, body: &str) -> Self {
+        self.on(url, Fixture::Response(HttpResponse::new(status, body)))
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.borrow().clone()
+    }
+
+    fn next(&self, url: &str) -> Option<Fixture> {
+        let mut fixtures = self.fixtures.borrow_mut();
+        let queue = fixtures.get_mut(url)?;
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+}
+
+impl HttpClient for FixtureClient {
+    fn get<'a>(
+        &'a self,
+        request: HttpRequest,
+        _signal: Option<&'a AbortSignal>,
+    ) -> LocalBoxFuture<'a, Result<HttpResponse, FetchError>> {
+        let fixture = self.next(&request.url);
+        let url = request.url.clone();
+        self.requests.borrow_mut().push(request);
+        async move {
+            match fixture {
+                Some(Fixture::Response(response)) => Ok(response),
+                Some(Fixture::NetworkError(e)) => Err(FetchError::Network(e)),
+                Some(Fixture::Timeout) => Err(FetchError::Network("timed out".to_string())),
+                None => Err(FetchError::Network(format!("no fixture for {}", url))),
+            }
+        }
+        .boxed_local()
+    }
+}
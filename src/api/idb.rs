@@ -0,0 +1,364 @@
+// IndexedDB cache store: room for large payloads, LRU eviction, localStorage fallback
+use crate::api::clock::{Clock, SystemClock};
+use crate::api::store::{AsyncStore, KeyValueStore, LocalStore};
+use futures::channel::oneshot;
+use futures::future::LocalBoxFuture;
+use gloo_events::EventListener;
+use serde::{Deserialize, Serialize};
+use std::cell::{OnceCell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::IdbTransactionMode::{Readonly, Readwrite};
+use web_sys::{DomException, EventTarget, IdbDatabase, IdbRequest, IdbTransaction};
+
+const DB_NAME: &str = "portfolio_cache";
+const DB_VERSION: u32 = 1;
+/// Payload strings, keyed by cache key
+const VALUES: &str = "values";
+/// [`EntryMeta`] JSON per key, read in one `getAll` when choosing what to evict
+const META: &str = "meta";
+
+/// Payload bytes kept before least-recently-used entries are evicted.
+pub const DEFAULT_BUDGET_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Size and recency of one stored entry.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EntryMeta {
+    pub key: String,
+    /// Payload length in bytes
+    pub size: u64,
+    /// Unix milliseconds of the last read or write
+    pub last_access: u64,
+}
+
+/// Keys to evict, least recently used first, until `entries` fit in `budget` bytes.
+///
+/// `keep` (the entry being written) is never chosen, even if it alone exceeds the budget.
+pub fn eviction_plan(entries: &[EntryMeta], budget: u64, keep: &str) -> Vec<String> {
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+    let mut candidates: Vec<&EntryMeta> = entries.iter().filter(|e| e.key != keep).collect();
+    candidates.sort_by_key(|e| e.last_access);
+    let mut evict = Vec::new();
+    for entry in candidates {
+        if total <= budget {
+            break;
+        }
+        total -= entry.size;
+        evict.push(entry.key.clone());
+    }
+    evict
+}
+
+/// Replaces (or adds) the entry for `key` with its new size and access time.
+fn upsert(entries: &mut Vec<EntryMeta>, meta: EntryMeta) {
+    entries.retain(|e| e.key != meta.key);
+    entries.push(meta);
+}
+
+type IdbResult<T> = Result<T, JsValue>;
+
+/// Resolves true when `target` fires `ok`, false when it fires any of `fail` first.
+async fn first_event(target: &EventTarget, ok: &'static str, fail: &[&'static str]) -> bool {
+    let (tx, rx) = oneshot::channel();
+    let tx = Rc::new(RefCell::new(Some(tx)));
+    let listen = |event: &'static str, outcome: bool| {
+        let tx = tx.clone();
+        EventListener::once(target, event, move |_| {
+            if let Some(tx) = tx.borrow_mut().take() {
+                let _ = tx.send(outcome);
+            }
+        })
+    };
+    let _listeners: Vec<EventListener> = std::iter::once(listen(ok, true))
+        .chain(fail.iter().map(|&event| listen(event, false)))
+        .collect();
+    rx.await.unwrap_or(false)
+}
+
+/// The request's result once it succeeds, or its `DOMException`.
+async fn settle(request: &IdbRequest) -> IdbResult<JsValue> {
+    if first_event(request, "success", &["error"]).await {
+        request.result()
+    } else {
+        Err(request
+            .error()
+            .ok()
+            .flatten()
+            .map_or_else(|| "request failed".into(), JsValue::from))
+    }
+}
+
+/// Waits for `tx` to commit. Quota errors surface here rather than on the request.
+async fn committed(tx: &IdbTransaction) -> IdbResult<()> {
+    if first_event(tx, "complete", &["abort", "error"]).await {
+        Ok(())
+    } else {
+        Err(tx
+            .error()
+            .map_or_else(|| "transaction aborted".into(), JsValue::from))
+    }
+}
+
+fn is_quota_error(error: &JsValue) -> bool {
+    error
+        .dyn_ref::<DomException>()
+        .is_some_and(|e| e.name() == "QuotaExceededError")
+}
+
+/// One IndexedDB database holding cache payloads under a byte budget.
+pub struct IdbStore {
+    db: IdbDatabase,
+    budget: u64,
+}
+
+impl Drop for IdbStore {
+    fn drop(&mut self) {
+        self.db.close();
+    }
+}
+
+impl IdbStore {
+    /// Opens (creating or upgrading) the cache database.
+    pub async fn open(budget: u64) -> IdbResult<Self> {
+        let factory = web_sys::window()
+            .ok_or("no window")?
+            .indexed_db()?
+            .ok_or("IndexedDB unavailable")?;
+        let request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+        let upgrading = request.clone();
+        let _upgrade = EventListener::once(&request, "upgradeneeded", move |_| {
+            let Ok(db) = upgrading.result().and_then(|r| r.dyn_into::<IdbDatabase>()) else {
+                return;
+            };
+            for name in [VALUES, META] {
+                if !db.object_store_names().contains(name) {
+                    let _ = db.create_object_store(name);
+                }
+            }
+        });
+        let db = settle(&request).await?.dyn_into::<IdbDatabase>()?;
+        Ok(Self { db, budget })
+    }
+
+    fn transaction(&self, mode: web_sys::IdbTransactionMode) -> IdbResult<IdbTransaction> {
+        let names = js_sys::Array::of2(&VALUES.into(), &META.into());
+        self.db.transaction_with_str_sequence_and_mode(&names, mode)
+    }
+
+    pub async fn get(&self, key: &str) -> IdbResult<Option<String>> {
+        let tx = self.transaction(Readonly)?;
+        let value = settle(&tx.object_store(VALUES)?.get(&key.into())?).await?;
+        let Some(value) = value.as_string() else {
+            return Ok(None);
+        };
+        // Bump recency; failing here only skews the eviction order
+        let _ = self.touch(key, value.len() as u64).await;
+        Ok(Some(value))
+    }
+
+    /// Stores `value`, first evicting least-recently-used entries to stay under the budget.
+    ///
+    /// If the browser's own quota is smaller than the budget, evicts the older half of
+    /// everything else and retries once.
+    pub async fn put(&self, key: &str, value: &str) -> IdbResult<()> {
+        let meta = self.meta(key, value.len() as u64);
+        let mut entries = self.entries().await?;
+        upsert(&mut entries, meta.clone());
+        self.delete_all(&eviction_plan(&entries, self.budget, key))
+            .await?;
+
+        match self.put_entry(value, &meta).await {
+            Err(e) if is_quota_error(&e) => {
+                let entries = self.entries().await?;
+                let total: u64 = entries.iter().map(|e| e.size).sum();
+                self.delete_all(&eviction_plan(&entries, total / 2, key))
+                    .await?;
+                self.put_entry(value, &meta).await
+            }
+            result => result,
+        }
+    }
+
+    pub async fn delete(&self, key: &str) -> IdbResult<()> {
+        self.delete_all(&[key.to_string()]).await
+    }
+
+    fn meta(&self, key: &str, size: u64) -> EntryMeta {
+        EntryMeta {
+            key: key.to_string(),
+            size,
+            last_access: SystemClock.now_ms(),
+        }
+    }
+
+    async fn touch(&self, key: &str, size: u64) -> IdbResult<()> {
+        let tx = self.transaction(Readwrite)?;
+        let meta = serde_json::to_string(&self.meta(key, size)).expect("meta serializes");
+        tx.object_store(META)?
+            .put_with_key(&meta.into(), &key.into())?;
+        committed(&tx).await
+    }
+
+    async fn put_entry(&self, value: &str, meta: &EntryMeta) -> IdbResult<()> {
+        let tx = self.transaction(Readwrite)?;
+        let key = JsValue::from_str(&meta.key);
+        let json = serde_json::to_string(meta).expect("meta serializes");
+        tx.object_store(VALUES)?.put_with_key(&value.into(), &key)?;
+        tx.object_store(META)?.put_with_key(&json.into(), &key)?;
+        committed(&tx).await
+    }
+
+    async fn entries(&self) -> IdbResult<Vec<EntryMeta>> {
+        let tx = self.transaction(Readonly)?;
+        let all = settle(&tx.object_store(META)?.get_all()?).await?;
+        Ok(js_sys::Array::from(&all)
+            .iter()
+            .filter_map(|v| v.as_string())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    async fn delete_all(&self, keys: &[String]) -> IdbResult<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let tx = self.transaction(Readwrite)?;
+        let values = tx.object_store(VALUES)?;
+        let meta = tx.object_store(META)?;
+        for key in keys {
+            let key = JsValue::from_str(key);
+            values.delete(&key)?;
+            meta.delete(&key)?;
+        }
+        committed(&tx).await
+    }
+}
+
+/// Cache store used in the browser: IndexedDB, or localStorage where IndexedDB is missing
+/// or refuses to open (e.g. some private-browsing modes).
+///
+/// Entries written to localStorage by earlier builds move into IndexedDB on first read.
+pub struct BrowserCache {
+    budget: u64,
+    /// Unset until the first access; `None` once opening IndexedDB has failed
+    idb: OnceCell<Option<Rc<IdbStore>>>,
+}
+
+impl BrowserCache {
+    pub fn new(budget: u64) -> Self {
+        Self {
+            budget,
+            idb: OnceCell::new(),
+        }
+    }
+
+    async fn idb(&self) -> Option<Rc<IdbStore>> {
+        if let Some(idb) = self.idb.get() {
+            return idb.clone();
+        }
+        let opened = IdbStore::open(self.budget).await;
+        if let Err(ref e) = opened {
+            web_sys::console::warn_2(&"IndexedDB unavailable, caching in localStorage:".into(), e);
+        }
+        // A concurrent first access may have opened it already; keep that one
+        let _ = self.idb.set(opened.ok().map(Rc::new));
+        self.idb.get().cloned().flatten()
+    }
+}
+
+impl AsyncStore for BrowserCache {
+    fn load<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Option<String>> {
+        Box::pin(async move {
+            let Some(idb) = self.idb().await else {
+                return LocalStore.get(key);
+            };
+            match idb.get(key).await {
+                Ok(Some(value)) => Some(value),
+                // Miss: a legacy localStorage entry, or a write that fell back to it
+                Ok(None) => {
+                    let value = LocalStore.get(key)?;
+                    if idb.put(key, &value).await.is_ok() {
+                        LocalStore.remove(key);
+                    }
+                    Some(value)
+                }
+                Err(_) => LocalStore.get(key),
+            }
+        })
+    }
+
+    fn save<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(idb) = self.idb().await {
+                if idb.put(key, value).await.is_ok() {
+                    return;
+                }
+                // Drop the older copy so reads fall through to the localStorage one
+                let _ = idb.delete(key).await;
+            }
+            LocalStore.set(key, value);
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(idb) = self.idb().await {
+                let _ = idb.delete(key).await;
+            }
+            LocalStore.remove(key);
+        })
+    }
+}
+
+thread_local! {
+    static BROWSER_CACHE: &'static BrowserCache =
+        Box::leak(Box::new(BrowserCache::new(DEFAULT_BUDGET_BYTES)));
+}
+
+/// The page-wide [`BrowserCache`], shared so IndexedDB is opened once.
+pub fn browser_cache() -> &'static BrowserCache {
+    BROWSER_CACHE.with(|cache| *cache)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(key: &str, size: u64, last_access: u64) -> EntryMeta {
+        EntryMeta {
+            key: key.to_string(),
+            size,
+            last_access,
+        }
+    }
+
+    #[test]
+    fn nothing_evicted_under_budget() {
+        let entries = [meta("a", 10, 1), meta("b", 10, 2)];
+        assert!(eviction_plan(&entries, 20, "a").is_empty());
+    }
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        let entries = [
+            meta("new", 10, 9),
+            meta("b", 10, 5),
+            meta("a", 10, 1),
+            meta("c", 10, 7),
+        ];
+        assert_eq!(eviction_plan(&entries, 25, "new"), ["a", "b"]);
+    }
+
+    #[test]
+    fn never_evicts_the_entry_being_written() {
+        let entries = [meta("big", 100, 1), meta("a", 10, 2)];
+        assert_eq!(eviction_plan(&entries, 50, "big"), ["a"]);
+    }
+
+    #[test]
+    fn upsert_replaces_previous_size() {
+        let mut entries = vec![meta("a", 10, 1), meta("b", 10, 2)];
+        upsert(&mut entries, meta("a", 30, 3));
+        assert_eq!(entries, [meta("b", 10, 2), meta("a", 30, 3)]);
+    }
+}
@@ -0,0 +1,81 @@
+// In-flight registry: concurrent refreshes share one request, newer ones cancel older ones
+use crate::api::github::{self, Backend, FetchOptions};
+use crate::api::FetchOutcome;
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use std::cell::RefCell;
+use web_sys::AbortController;
+
+struct InFlight {
+    generation: u64,
+    options: FetchOptions,
+    controller: Option<AbortController>,
+    future: Shared<LocalBoxFuture<'static, FetchOutcome>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Bumped whenever a refresh starts that supersedes the previous one
+    generation: u64,
+    current: Option<InFlight>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// Fetches repos (with static fallback), de-duplicated and cancellable.
+///
+/// A call the request in flight already covers (see [`FetchOptions::covers`]) joins it, so
+/// a scheduled refresh never cancels a manual one. Any other call, e.g. a manual refresh
+/// while a scheduled one is pending, aborts it and starts a new one. Returns `None` if a
+/// newer refresh started while this one was pending, so callers never apply out-of-order
+/// results.
+pub async fn fetch_latest(options: FetchOptions) -> Option<FetchOutcome> {
+    let (generation, future) = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if let Some(ref current) = registry.current {
+            if current.options.covers(&options) {
+                return (current.generation, current.future.clone());
+            }
+        }
+        if let Some(previous) = registry.current.take() {
+            if let Some(controller) = previous.controller {
+                controller.abort();
+            }
+        }
+        registry.generation += 1;
+        let generation = registry.generation;
+        let controller = AbortController::new().ok();
+        let signal = controller.as_ref().map(AbortController::signal);
+        let request_options = options.clone();
+        let future = async move {
+            let result =
+                github::fetch_repos_using(Backend::browser(), &request_options, signal.as_ref())
+                    .await;
+            github::or_static_fallback(result)
+        }
+        .boxed_local()
+        .shared();
+        registry.current = Some(InFlight {
+            generation,
+            options,
+            controller,
+            future: future.clone(),
+        });
+        (generation, future)
+    });
+
+    let outcome = future.await;
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if registry
+            .current
+            .as_ref()
+            .is_some_and(|c| c.generation == generation)
+        {
+            registry.current = None;
+        }
+        // Late response from a superseded request: drop it
+        (registry.generation == generation).then_some(outcome)
+    })
+}
@@ -0,0 +1,36 @@
+// Language byte counts per repo, for the stacked bar on each card
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::decode;
+use crate::api::FetchError;
+use crate::models::{Languages, RepoKey};
+
+/// Language mixes change slowly; one lookup per repo per day is plenty.
+pub const LANGUAGES: DetailKind = DetailKind {
+    name: "languages",
+    ttl_ms: 24 * 60 * 60 * 1000,
+};
+
+pub fn languages_url(repo: &RepoKey) -> String {
+    format!("https://api.github.com/repos/{}/languages", repo.slug())
+}
+
+/// Byte counts per language for `repo`, from cache or GitHub.
+pub async fn fetch_languages(repo: &RepoKey) -> Result<Languages, FetchError> {
+    fetch_languages_using(Backend::browser(), repo).await
+}
+
+/// Like [`fetch_languages`], over any [`Backend`].
+pub async fn fetch_languages_using(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+) -> Result<Languages, FetchError> {
+    details::cached_or_fetch(backend, &LANGUAGES, repo, || async {
+        let request = HttpRequest::get(&languages_url(repo))
+            .header("Accept", "application/vnd.github.v3+json");
+        let response = github::send(backend, request, None).await?;
+        decode(&response.body)
+    })
+    .await
+}
@@ -1 +1,21 @@
+pub mod cache;
+pub mod clock;
+pub mod commit_activity;
+pub mod details;
+pub mod error;
+pub mod events;
 pub mod github;
+pub mod http;
+pub mod idb;
+pub mod inflight;
+pub mod languages;
+pub mod parse;
+pub mod rate_limit;
+pub mod readme;
+pub mod releases;
+pub mod retry;
+pub mod screenshots;
+pub mod store;
+pub mod tabs;
+
+pub use error::{DataSource, FetchError, FetchOutcome};
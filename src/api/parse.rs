@@ -0,0 +1,106 @@
+// Pure parsing helpers for GitHub responses
+use crate::api::FetchError;
+use serde::de::DeserializeOwned;
+
+/// Extracts the `rel="next"` target from a GitHub `Link` header.
+pub fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let mut segments = part.split(';');
+        let url = segments.next()?.trim();
+        let is_next = segments.any(|param| {
+            let param = param.trim();
+            param == "rel=\"next\"" || param == "rel=next"
+        });
+        if !is_next {
+            return None;
+        }
+        url.strip_prefix('<')
+            .and_then(|u| u.strip_suffix('>'))
+            .map(str::to_string)
+    })
+}
+
+/// Deserializes a JSON body, reporting the path of the first mismatching field.
+pub fn decode<T: DeserializeOwned>(body: &str) -> Result<T, FetchError> {
+    let de = &mut serde_json::Deserializer::from_str(body);
+    serde_path_to_error::deserialize(de).map_err(|e| FetchError::Decode {
+        path: e.path().to_string(),
+        message: e.inner().to_string(),
+    })
+}
+
+/// Decodes standard base64 as GitHub sends file contents: padded, with line breaks every
+/// 60 characters. Whitespace is skipped; `None` on any other invalid input.
+pub fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+    for c in encoded.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => {
+                padding += 1;
+                continue;
+            }
+            _ => return None,
+        };
+        // Data after padding
+        if padding > 0 {
+            return None;
+        }
+        buffer = buffer << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    (padding <= 2).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Repo;
+
+    #[test]
+    fn finds_next_among_several_relations() {
+        let link = r#"<https://api.github.com/x?page=2>; rel="next", <https://api.github.com/x?page=5>; rel="last""#;
+        assert_eq!(
+            next_page_url(link).as_deref(),
+            Some("https://api.github.com/x?page=2")
+        );
+    }
+
+    #[test]
+    fn no_next_on_last_page() {
+        let link = r#"<https://api.github.com/x?page=1>; rel="prev", <https://api.github.com/x?page=1>; rel="first""#;
+        assert_eq!(next_page_url(link), None);
+        assert_eq!(next_page_url(""), None);
+    }
+
+    #[test]
+    fn decode_error_carries_path() {
+        let body = r#"[{"name":"a","html_url":"u","description":null,"language":null,"updated_at":"","stargazers_count":"x"}]"#;
+        match decode::<Vec<Repo>>(body) {
+            Err(FetchError::Decode { path, .. }) => assert_eq!(path, "[0].stargazers_count"),
+            other => panic!("expected decode error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decodes_wrapped_base64() {
+        assert_eq!(
+            decode_base64("IyBTZW5ldApB\nbiBvbGQgZ2FtZS4=\n").as_deref(),
+            Some(&b"# Senet\nAn old game."[..])
+        );
+        assert_eq!(decode_base64("").as_deref(), Some(&b""[..]));
+        assert_eq!(decode_base64("YQ=x"), None);
+        assert_eq!(decode_base64("Y*=="), None);
+    }
+}
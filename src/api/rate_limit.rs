@@ -0,0 +1,163 @@
+// GitHub rate-limit bookkeeping: parsed from response headers, persisted next to the cache
+use crate::api::clock::Clock;
+use crate::api::http::HttpResponse;
+use crate::api::store::KeyValueStore;
+use serde::{Deserialize, Serialize};
+
+const RATE_LIMIT_KEY: &str = "portfolio_rate_limit";
+/// Requests per window kept for the repo list; optional per-repo lookups stop before this.
+pub const OPTIONAL_RESERVE: u32 = 10;
+
+/// Last known GitHub API quota.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RateLimit {
+    /// `X-RateLimit-Limit`
+    pub limit: Option<u32>,
+    /// `X-RateLimit-Remaining`
+    pub remaining: Option<u32>,
+    /// `X-RateLimit-Reset`, Unix seconds
+    pub reset_at: Option<u64>,
+    /// No requests should be sent before this Unix time (exhausted quota or `Retry-After`)
+    pub blocked_until: Option<u64>,
+}
+
+impl RateLimit {
+    /// Parses rate-limit headers from a response received at `now_secs`.
+    pub fn from_response(response: &HttpResponse, now_secs: u64) -> Self {
+        let status = response.status;
+        let parse = |name: &str| {
+            response
+                .header(name)
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let limit = parse("x-ratelimit-limit").map(|v| v as u32);
+        let remaining = parse("x-ratelimit-remaining").map(|v| v as u32);
+        let reset_at = parse("x-ratelimit-reset");
+        let retry_after = parse("retry-after").map(|secs| now_secs + secs);
+
+        let exhausted = remaining == Some(0) && matches!(status, 403 | 429);
+        let blocked_until = match (retry_after, exhausted.then_some(reset_at).flatten()) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            limit,
+            remaining,
+            reset_at,
+            blocked_until,
+        }
+    }
+
+    /// True if requests would still be held back at Unix time `at`.
+    pub fn blocks_at(&self, at: u64) -> bool {
+        self.blocked_until.is_some_and(|t| at < t)
+    }
+}
+
+/// Returns the last persisted quota, if any.
+pub fn load(store: &dyn KeyValueStore) -> Option<RateLimit> {
+    serde_json::from_str(&store.get(RATE_LIMIT_KEY)?).ok()
+}
+
+/// Persists quota info. Responses without rate-limit headers keep the previous values.
+pub fn store(store: &dyn KeyValueStore, rate_limit: &RateLimit) {
+    if rate_limit == &RateLimit::default() {
+        return;
+    }
+    if let Ok(json) = serde_json::to_string(rate_limit) {
+        store.set(RATE_LIMIT_KEY, &json);
+    }
+}
+
+/// True while GitHub asked us to back off; every refresh trigger should check this first.
+pub fn is_blocked(store: &dyn KeyValueStore, clock: &dyn Clock) -> bool {
+    load(store).is_some_and(|r| r.blocks_at(clock.now_secs()))
+}
+
+/// True if optional requests (per-repo details) may spend quota right now: not blocked,
+/// and more than [`OPTIONAL_RESERVE`] requests left in the current window.
+pub fn allows_optional(store: &dyn KeyValueStore, clock: &dyn Clock) -> bool {
+    let Some(limit) = load(store) else {
+        return true;
+    };
+    let now = clock.now_secs();
+    if limit.blocks_at(now) {
+        return false;
+    }
+    // A window that has already reset has a full quota again
+    let window_over = limit.reset_at.is_some_and(|reset| now >= reset);
+    window_over || limit.remaining.is_none_or(|left| left > OPTIONAL_RESERVE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::clock::MockClock;
+    use crate::api::store::MemoryStore;
+
+    #[test]
+    fn exhausted_quota_blocks_until_reset() {
+        let response = HttpResponse::new(403, "")
+            .with_header("X-RateLimit-Limit", "60")
+            .with_header("X-RateLimit-Remaining", "0")
+            .with_header("X-RateLimit-Reset", "2000");
+        let limit = RateLimit::from_response(&response, 1_000);
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(limit.blocked_until, Some(2_000));
+        assert!(limit.blocks_at(1_999));
+        assert!(!limit.blocks_at(2_000));
+    }
+
+    #[test]
+    fn zero_remaining_on_success_does_not_block() {
+        let response = HttpResponse::new(200, "")
+            .with_header("X-RateLimit-Remaining", "0")
+            .with_header("X-RateLimit-Reset", "2000");
+        assert_eq!(
+            RateLimit::from_response(&response, 1_000).blocked_until,
+            None
+        );
+    }
+
+    #[test]
+    fn retry_after_is_relative_to_now() {
+        let response = HttpResponse::new(429, "").with_header("Retry-After", "30");
+        assert_eq!(
+            RateLimit::from_response(&response, 1_000).blocked_until,
+            Some(1_030)
+        );
+    }
+
+    #[test]
+    fn optional_requests_keep_a_reserve() {
+        let store = MemoryStore::new();
+        let clock = MockClock::at_ms(1_000_000);
+        assert!(allows_optional(&store, &clock));
+
+        let low = RateLimit {
+            remaining: Some(OPTIONAL_RESERVE),
+            reset_at: Some(1_600),
+            ..RateLimit::default()
+        };
+        super::store(&store, &low);
+        assert!(!allows_optional(&store, &clock));
+        clock.advance_secs(600);
+        assert!(allows_optional(&store, &clock));
+    }
+
+    #[test]
+    fn headerless_responses_keep_previous_record() {
+        let store = MemoryStore::new();
+        let clock = MockClock::at_ms(1_000_000);
+        let blocked = RateLimit {
+            blocked_until: Some(1_500),
+            ..RateLimit::default()
+        };
+        super::store(&store, &blocked);
+        super::store(&store, &RateLimit::default());
+        assert_eq!(load(&store), Some(blocked));
+        assert!(is_blocked(&store, &clock));
+        clock.advance_secs(500);
+        assert!(!is_blocked(&store, &clock));
+    }
+}
@@ -0,0 +1,264 @@
+// README per repo: fetched once, parsed to a Markdown document with its relative links and
+// images pointed back at the repo, and cached in that parsed form
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::{decode, decode_base64};
+use crate::api::FetchError;
+use crate::markdown::{self, Block, Document, UrlKind};
+use crate::models::RepoKey;
+use serde::{Deserialize, Serialize};
+
+/// READMEs change more often than language mixes, and are only opened on demand.
+pub const README: DetailKind = DetailKind {
+    name: "readme",
+    ttl_ms: 6 * 60 * 60 * 1000,
+};
+
+pub fn readme_url(repo: &RepoKey) -> String {
+    format!("https://api.github.com/repos/{}/readme", repo.slug())
+}
+
+/// `GET /repos/{owner}/{repo}/readme`
+#[derive(Debug, Deserialize)]
+struct ReadmeFile {
+    sha: String,
+    path: String,
+    content: String,
+    encoding: String,
+    /// e.g. `https://github.com/o/r/blob/main/README.md`
+    html_url: String,
+    /// e.g. `https://raw.githubusercontent.com/o/r/main/README.md`
+    download_url: Option<String>,
+}
+
+/// A repo's README, ready to render.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Readme {
+    /// Git blob SHA of the README; the parsed document is reused while it is unchanged
+    pub sha: String,
+    pub html_url: String,
+    pub document: Document,
+}
+
+/// The README of `repo`, from cache or GitHub; `None` when the repo has none.
+pub async fn fetch_readme(repo: &RepoKey) -> Result<Option<Readme>, FetchError> {
+    fetch_readme_using(Backend::browser(), repo).await
+}
+
+/// Like [`fetch_readme`], over any [`Backend`].
+pub async fn fetch_readme_using(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+) -> Result<Option<Readme>, FetchError> {
+    details::cached_or_refresh(
+        backend,
+        &README,
+        repo,
+        |previous: Option<Option<Readme>>| async move {
+            let request = HttpRequest::get(&readme_url(repo))
+                .header("Accept", "application/vnd.github.v3+json");
+            let response = match github::send(backend, request, None).await {
+                // Cached like any other answer, so repos without one are not asked again
+                Err(FetchError::Http { status: 404 }) => return Ok(None),
+                result => result?,
+            };
+            let file: ReadmeFile = decode(&response.body)?;
+            match previous.flatten() {
+                Some(previous) if previous.sha == file.sha => Ok(Some(previous)),
+                _ => render(file).map(Some),
+            }
+        },
+    )
+    .await
+}
+
+fn render(file: ReadmeFile) -> Result<Readme, FetchError> {
+    let bytes = (file.encoding == "base64")
+        .then(|| decode_base64(&file.content))
+        .flatten()
+        .ok_or_else(|| FetchError::Decode {
+            path: "content".to_string(),
+            message: format!("cannot decode {} content", file.encoding),
+        })?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let mut document = if is_markdown(&file.path) {
+        markdown::parse(&text)
+    } else {
+        // README.txt, README.rst, …: shown as written
+        Document {
+            blocks: vec![Block::Code {
+                lang: None,
+                text: text.trim_end().to_string(),
+            }],
+        }
+    };
+    let links = RepoBase::new(&file.html_url, 4);
+    let images = file
+        .download_url
+        .as_deref()
+        .and_then(|url| RepoBase::new(url, 3));
+    document.rewrite_urls(&mut |kind, url| {
+        let base = match kind {
+            UrlKind::Link => links.as_ref(),
+            UrlKind::Image => images.as_ref().or(links.as_ref()),
+        };
+        rewrite_url(url, &file.html_url, base)
+    });
+
+    Ok(Readme {
+        sha: file.sha,
+        html_url: file.html_url,
+        document,
+    })
+}
+
+fn is_markdown(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+    match name.rsplit_once('.') {
+        Some((_, ext)) => matches!(ext, "md" | "markdown" | "mdown" | "mkd"),
+        None => true,
+    }
+}
+
+/// Where the README lives, for resolving relative URLs against it.
+struct RepoBase {
+    /// `scheme://host`
+    origin: String,
+    /// Path segments of the repo at the README's ref, e.g. `o/r/blob/main`; the target of
+    /// `/`-rooted paths, as on GitHub
+    root: Vec<String>,
+    /// Path segments below `root` of the README's directory
+    dir: Vec<String>,
+}
+
+impl RepoBase {
+    /// `depth` is how many path segments of `file_url` make up the root: 4 for
+    /// `github.com/o/r/blob/ref`, 3 for `raw.githubusercontent.com/o/r/ref`.
+    fn new(file_url: &str, depth: usize) -> Option<Self> {
+        let (scheme, rest) = file_url.split_once("://")?;
+        let (host, path) = rest.split_once('/')?;
+        let segments: Vec<String> = path.split('/').map(str::to_string).collect();
+        if segments.len() <= depth {
+            return None;
+        }
+        Some(Self {
+            origin: format!("{}://{}", scheme, host),
+            root: segments[..depth].to_vec(),
+            dir: segments[depth..segments.len() - 1].to_vec(),
+        })
+    }
+
+    /// Resolves like a browser would against the README's page, so `../../issues` from a
+    /// top-level README still reaches the repo's issues.
+    fn resolve(&self, relative: &str) -> String {
+        let (path, suffix) = relative.split_at(relative.find(['?', '#']).unwrap_or(relative.len()));
+        let mut segments: Vec<&str> = self.root.iter().map(String::as_str).collect();
+        if !path.starts_with('/') {
+            segments.extend(self.dir.iter().map(String::as_str));
+        }
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        let mut url = self.origin.clone();
+        for segment in segments {
+            url.push('/');
+            url.push_str(segment);
+        }
+        url.push_str(suffix);
+        url
+    }
+}
+
+/// Absolute form of a README URL, or `None` for schemes that must not reach the page.
+fn rewrite_url(url: &str, html_url: &str, base: Option<&RepoBase>) -> Option<String> {
+    if url.is_empty() || !markdown::is_safe_url(url) {
+        return None;
+    }
+    if has_scheme(url) {
+        return Some(url.to_string());
+    }
+    if let Some(rest) = url.strip_prefix("//") {
+        return Some(format!("https://{}", rest));
+    }
+    if url.starts_with('#') {
+        // Headings get their anchors on GitHub's rendering, not ours
+        return Some(format!("{}{}", html_url, url));
+    }
+    base.map(|base| base.resolve(url))
+}
+
+fn has_scheme(url: &str) -> bool {
+    url.split_once(':')
+        .is_some_and(|(scheme, _)| !scheme.contains(['/', '?', '#']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "https://github.com/o/r/blob/main/docs/README.md";
+    const RAW: &str = "https://raw.githubusercontent.com/o/r/main/docs/README.md";
+
+    fn resolve(kind: UrlKind, url: &str) -> Option<String> {
+        let base = match kind {
+            UrlKind::Link => RepoBase::new(HTML, 4),
+            UrlKind::Image => RepoBase::new(RAW, 3),
+        };
+        rewrite_url(url, HTML, base.as_ref())
+    }
+
+    #[test]
+    fn relative_urls_resolve_against_the_readme() {
+        let link = |url| resolve(UrlKind::Link, url);
+        let image = |url| resolve(UrlKind::Image, url);
+        assert_eq!(
+            link("setup.md#linux").as_deref(),
+            Some("https://github.com/o/r/blob/main/docs/setup.md#linux")
+        );
+        assert_eq!(
+            link("../LICENSE").as_deref(),
+            Some("https://github.com/o/r/blob/main/LICENSE")
+        );
+        assert_eq!(
+            link("../../../issues").as_deref(),
+            Some("https://github.com/o/r/issues")
+        );
+        assert_eq!(
+            image("/assets/./shot.png?v=2").as_deref(),
+            Some("https://raw.githubusercontent.com/o/r/main/assets/shot.png?v=2")
+        );
+        assert_eq!(
+            link("#usage").as_deref(),
+            Some("https://github.com/o/r/blob/main/docs/README.md#usage")
+        );
+    }
+
+    #[test]
+    fn absolute_and_unsafe_urls() {
+        let link = |url| resolve(UrlKind::Link, url);
+        assert_eq!(link("https://x.io/a").as_deref(), Some("https://x.io/a"));
+        assert_eq!(
+            link("//cdn.io/a.png").as_deref(),
+            Some("https://cdn.io/a.png")
+        );
+        assert_eq!(link("mailto:me@x.io").as_deref(), Some("mailto:me@x.io"));
+        assert_eq!(link("javascript:alert(1)"), None);
+        assert_eq!(link(""), None);
+    }
+
+    #[test]
+    fn only_markdown_files_are_parsed() {
+        assert!(is_markdown("README.md"));
+        assert!(is_markdown("docs/Readme.MARKDOWN"));
+        assert!(is_markdown("README"));
+        assert!(!is_markdown("README.rst"));
+    }
+}
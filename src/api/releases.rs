@@ -0,0 +1,161 @@
+// Newest version per repo for the card's version badge: the latest release, or the highest
+// version tag for repos that tag versions without publishing releases
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::decode;
+use crate::api::FetchError;
+use crate::models::timestamp::{self, Timestamp};
+use crate::models::{Release, RepoKey};
+use serde::Deserialize;
+
+/// Download counts move daily, versions less often.
+pub const RELEASE: DetailKind = DetailKind {
+    name: "release",
+    ttl_ms: 6 * 60 * 60 * 1000,
+};
+
+/// Newest non-draft, non-prerelease release.
+pub fn latest_release_url(repo: &RepoKey) -> String {
+    format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        repo.slug()
+    )
+}
+
+/// A page of tags. GitHub sorts them by name, not date, so `v0.9.0` comes before `v0.10.0`;
+/// the highest version on the page is picked instead.
+pub fn tags_url(repo: &RepoKey) -> String {
+    format!(
+        "https://api.github.com/repos/{}/tags?per_page=100",
+        repo.slug()
+    )
+}
+
+/// `GET /repos/{owner}/{repo}/releases/latest`
+#[derive(Debug, Deserialize)]
+struct ReleaseJson {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    html_url: String,
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    published_at: Option<Timestamp>,
+    #[serde(default)]
+    assets: Vec<AssetJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetJson {
+    #[serde(default)]
+    download_count: u64,
+}
+
+/// One entry of `GET /repos/{owner}/{repo}/tags`
+#[derive(Debug, Deserialize)]
+struct TagJson {
+    name: String,
+}
+
+/// The newest version of `repo`, from cache or GitHub; `None` when it has neither releases
+/// nor tags. `slug` is [`Repo::slug`](crate::models::Repo::slug), which keeps the casing the
+/// key drops, for the tag page link.
+pub async fn fetch_release(repo: &RepoKey, slug: &str) -> Result<Option<Release>, FetchError> {
+    fetch_release_using(Backend::browser(), repo, slug).await
+}
+
+/// Like [`fetch_release`], over any [`Backend`].
+pub async fn fetch_release_using(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+    slug: &str,
+) -> Result<Option<Release>, FetchError> {
+    details::cached_or_fetch(backend, &RELEASE, repo, || async {
+        let request = HttpRequest::get(&latest_release_url(repo))
+            .header("Accept", "application/vnd.github.v3+json");
+        match github::send(backend, request, None).await {
+            // No published release: the newest tag is the next best version
+            Err(FetchError::Http { status: 404 }) => latest_tag(backend, repo, slug).await,
+            result => {
+                let release: ReleaseJson = decode(&result?.body)?;
+                Ok(Some(from_release(release)))
+            }
+        }
+    })
+    .await
+}
+
+async fn latest_tag(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+    slug: &str,
+) -> Result<Option<Release>, FetchError> {
+    let request =
+        HttpRequest::get(&tags_url(repo)).header("Accept", "application/vnd.github.v3+json");
+    let tags: Vec<TagJson> = match github::send(backend, request, None).await {
+        // Empty repos answer 404 here too
+        Err(FetchError::Http { status: 404 }) => Vec::new(),
+        result => decode(&result?.body)?,
+    };
+    Ok(newest_tag(tags).map(|tag| Release {
+        html_url: format!(
+            "https://github.com/{}/releases/tag/{}",
+            slug,
+            encode_segment(&tag.name)
+        ),
+        tag: tag.name,
+        tag_only: true,
+        ..Release::default()
+    }))
+}
+
+/// The tag with the highest version, e.g. `v0.10.0` over `v0.9.0`, and a release over its
+/// own pre-releases. Ties, and pages with no version-like tag at all, go to the first tag.
+fn newest_tag(tags: Vec<TagJson>) -> Option<TagJson> {
+    tags.into_iter().rev().max_by_key(|tag| version(&tag.name))
+}
+
+/// Numeric parts of a tag such as `v1.2.3-rc.1`, and whether it is a final release; `None`
+/// for tags that are not versions, which rank below all that are.
+fn version(tag: &str) -> Option<(Vec<u64>, bool)> {
+    let rest = tag.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let (core, pre) = match rest.find(['-', '+']) {
+        Some(i) => (&rest[..i], rest[i..].starts_with('-')),
+        None => (rest, false),
+    };
+    let parts = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((parts, !pre))
+}
+
+/// Percent-encodes everything but unreserved characters, for a tag used as one path segment.
+fn encode_segment(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn from_release(release: ReleaseJson) -> Release {
+    // GitHub fills `name` with the tag when no title was given
+    let name = release
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && *name != release.tag_name);
+    Release {
+        tag: release.tag_name,
+        name,
+        html_url: release.html_url,
+        published_at: release.published_at,
+        assets: release.assets.len(),
+        downloads: release.assets.iter().map(|a| a.download_count).sum(),
+        tag_only: false,
+    }
+}
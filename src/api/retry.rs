@@ -0,0 +1,222 @@
+// Retry policy for transient GitHub failures: exponential backoff with jitter
+use crate::api::github::Backend;
+use crate::api::{rate_limit, FetchError};
+use futures::future::{self, Either, FutureExt};
+use std::future::Future;
+use web_sys::AbortSignal;
+
+/// When and how often to retry a failed request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each one after
+    pub base_delay_ms: u32,
+    /// Upper bound for a single delay
+    pub max_delay_ms: u32,
+    /// Fraction of each delay that is randomized (0.0 = none, 1.0 = full jitter)
+    pub jitter: f64,
+    /// HTTP statuses worth retrying; network errors are always retried
+    pub retry_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+            jitter: 0.5,
+            retry_statuses: vec![500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// No retries at all.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn should_retry(&self, error: &FetchError) -> bool {
+        match error {
+            FetchError::Network(_) => true,
+            FetchError::Http { status } => self.retry_statuses.contains(status),
+            _ => false,
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based). `random` is in `[0, 1)`.
+    pub fn delay_ms(&self, retry: u32, random: f64) -> u32 {
+        let exp = self
+            .base_delay_ms
+            .saturating_mul(1u32 << retry.saturating_sub(1).min(16));
+        let capped = exp.min(self.max_delay_ms) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        (capped * (1.0 - jitter * random)) as u32
+    }
+}
+
+/// Runs `attempt` until it succeeds, fails permanently, or the policy gives up.
+///
+/// Gives up early when the tab is hidden, the request was aborted, or GitHub's rate limit
+/// would not reset before the next attempt; a backoff in progress ends as soon as the tab
+/// is hidden or the request aborted.
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    backend: Backend<'_>,
+    signal: Option<&AbortSignal>,
+    mut attempt: F,
+) -> Result<T, FetchError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, FetchError>>,
+{
+    let mut tries = 1;
+    loop {
+        let error = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        // An aborted request fails as a network error; it must not wait out a backoff first
+        if signal.is_some_and(AbortSignal::aborted) {
+            return Err(FetchError::Aborted);
+        }
+        if tries >= policy.max_attempts || !policy.should_retry(&error) {
+            return Err(error);
+        }
+        let delay = policy.delay_ms(tries, random());
+        let next_attempt = backend.clock.now_secs() + u64::from(delay / 1000);
+        if rate_limit::load(backend.store).is_some_and(|r| r.blocks_at(next_attempt)) {
+            return Err(error);
+        }
+        if tab_hidden() {
+            return Err(error);
+        }
+        let sleep = backend.clock.sleep(delay);
+        match future::select(sleep, interrupted(signal).boxed_local()).await {
+            Either::Left(_) => {}
+            Either::Right((Interrupt::Aborted, _)) => return Err(FetchError::Aborted),
+            Either::Right((Interrupt::Hidden, _)) => return Err(error),
+        }
+        tries += 1;
+    }
+}
+
+/// Why a backoff was cut short.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+enum Interrupt {
+    Aborted,
+    Hidden,
+}
+
+/// Resolves as soon as `signal` aborts or the tab is hidden.
+#[cfg(target_arch = "wasm32")]
+async fn interrupted(signal: Option<&AbortSignal>) -> Interrupt {
+    use futures::channel::oneshot;
+    use gloo_events::EventListener;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let (tx, rx) = oneshot::channel();
+    let tx = Rc::new(RefCell::new(Some(tx)));
+    let send = move |interrupt| {
+        if let Some(tx) = tx.borrow_mut().take() {
+            let _ = tx.send(interrupt);
+        }
+    };
+    let mut _listeners = Vec::new();
+    if let Some(signal) = signal {
+        let send = send.clone();
+        _listeners.push(EventListener::once(signal, "abort", move |_| {
+            send(Interrupt::Aborted)
+        }));
+    }
+    if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+        _listeners.push(EventListener::new(
+            &document,
+            "visibilitychange",
+            move |_| {
+                if tab_hidden() {
+                    send(Interrupt::Hidden);
+                }
+            },
+        ));
+    }
+    match rx.await {
+        Ok(interrupt) => interrupt,
+        // Neither can fire: wait out the backoff
+        Err(_) => future::pending().await,
+    }
+}
+
+/// Host builds only run tests, which have nothing to abort or hide.
+#[cfg(not(target_arch = "wasm32"))]
+async fn interrupted(_signal: Option<&AbortSignal>) -> Interrupt {
+    future::pending().await
+}
+
+#[cfg(target_arch = "wasm32")]
+fn tab_hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .is_some_and(|d| d.visibility_state() == web_sys::VisibilityState::Hidden)
+}
+
+/// Host builds only run tests, which have no document to hide.
+#[cfg(not(target_arch = "wasm32"))]
+fn tab_hidden() -> bool {
+    false
+}
+
+#[cfg(target_arch = "wasm32")]
+fn random() -> f64 {
+    js_sys::Math::random()
+}
+
+/// Host builds only run tests; a fixed midpoint keeps delays reproducible.
+#[cfg(not(target_arch = "wasm32"))]
+fn random() -> f64 {
+    0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_double_and_cap() {
+        let policy = RetryPolicy {
+            jitter: 0.0,
+            base_delay_ms: 100,
+            max_delay_ms: 500,
+            ..RetryPolicy::default()
+        };
+        let delays: Vec<u32> = (1..=5).map(|n| policy.delay_ms(n, 0.0)).collect();
+        assert_eq!(delays, [100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn jitter_only_shortens_delay() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            base_delay_ms: 1_000,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay_ms(1, 0.0), 1_000);
+        assert_eq!(policy.delay_ms(1, 1.0), 500);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(&FetchError::Network("offline".into())));
+        assert!(policy.should_retry(&FetchError::Http { status: 503 }));
+        assert!(!policy.should_retry(&FetchError::Http { status: 404 }));
+        assert!(!policy.should_retry(&FetchError::RateLimited { reset_at: None }));
+        assert!(!policy.should_retry(&FetchError::Aborted));
+    }
+}
@@ -0,0 +1,247 @@
+// Card images: a hand-picked screenshot when one is listed, otherwise one discovered from the
+// repo itself (README, file naming conventions)
+use crate::api::details::{self, DetailKind};
+use crate::api::github::{self, Backend};
+use crate::api::http::HttpRequest;
+use crate::api::parse::decode;
+use crate::api::readme;
+use crate::api::FetchError;
+use crate::models::RepoKey;
+use serde::Deserialize;
+
+/// Screenshots rarely change; a daily look is plenty.
+pub const SCREENSHOT: DetailKind = DetailKind {
+    name: "screenshot",
+    ttl_ms: 24 * 60 * 60 * 1000,
+};
+
+/// Manual overrides by `owner/name`, for repos whose discovered image is not the best one.
+const OVERRIDES: &[(&str, &str)] = &[
+    (
+        "azuree0/Senet",
+        "https://github.com/user-attachments/assets/b12746e5-fb64-41a4-b343-5ec77166cff6",
+    ),
+    (
+        "azuree0/Latrones",
+        "https://github.com/user-attachments/assets/182fd35b-a924-4749-8f37-9f48060ec49f",
+    ),
+    (
+        "azuree0/Chaturanga",
+        "https://github.com/user-attachments/assets/ebd040e8-8939-4ad7-bc43-e655a4ba6582",
+    ),
+    (
+        "azuree0/Go",
+        "https://github.com/user-attachments/assets/f52cbdc4-afe9-4169-8a03-ed025b6a834a",
+    ),
+    (
+        "azuree0/Game-of-Ur",
+        "https://github.com/user-attachments/assets/fe00622d-3483-47b2-b9ff-b00a17f4c159",
+    ),
+    (
+        "azuree0/Mehen",
+        "https://github.com/user-attachments/assets/b9a324c1-822d-49ed-b88e-13fbc2b17f04",
+    ),
+    (
+        "azuree0/Nard",
+        "https://github.com/user-attachments/assets/9cdc289f-cd3d-433f-af37-d508c45c7179",
+    ),
+    (
+        "azuree0/Silent-data-corruption",
+        "https://github.com/user-attachments/assets/1db52073-faf9-4e6d-895c-36c66dc1625d",
+    ),
+    (
+        "azuree0/Rubik",
+        "https://github.com/user-attachments/assets/5de4e3d7-b660-4ea4-a513-aca077b695cf",
+    ),
+    (
+        "azuree0/Liquid",
+        "https://github.com/user-attachments/assets/ac0f0af2-e95e-4591-b848-e30c89675822",
+    ),
+    (
+        "azuree0/Bria-ai",
+        "https://github.com/user-attachments/assets/ed5d7f08-27a7-40fb-a93a-6f2b4b89cab4",
+    ),
+    (
+        "azuree0/Aerospace",
+        "https://github.com/user-attachments/assets/86f9cac2-334b-4cf7-b374-d82dba4bd186",
+    ),
+];
+
+/// File names (without extension) checked at the repo root, then in `docs/`, best first.
+const ROOT_NAMES: &[&str] = &["screenshot", "preview"];
+const DOCS_NAMES: &[&str] = &["preview", "screenshot"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// The hand-picked screenshot for `repo`, if it has one.
+pub fn manual_screenshot(repo: &RepoKey) -> Option<&'static str> {
+    OVERRIDES
+        .iter()
+        .find(|(slug, _)| RepoKey::new("github", slug) == *repo)
+        .map(|(_, url)| *url)
+}
+
+/// The discovered screenshot for `repo`, from cache or GitHub; `None` when it has no image.
+pub async fn fetch_screenshot(repo: &RepoKey) -> Result<Option<String>, FetchError> {
+    fetch_screenshot_using(Backend::browser(), repo).await
+}
+
+/// Like [`fetch_screenshot`], over any [`Backend`].
+///
+/// Tries the first non-badge image in the README, then `screenshot.*` / `preview.*` at the
+/// root and in `docs/`. A README image settles it without listing any directories; finding
+/// none anywhere is cached as `None`, and the card draws a placeholder. Errors along the way
+/// are returned rather than skipped, so a rate-limited lookup is retried later instead of
+/// caching a missing image.
+pub async fn fetch_screenshot_using(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+) -> Result<Option<String>, FetchError> {
+    details::cached_or_derive(backend, &SCREENSHOT, repo, || async move {
+        let readme = readme::fetch_readme_using(backend, repo).await?;
+        let from_readme = readme.as_ref().and_then(|readme| {
+            readme
+                .document
+                .images()
+                .into_iter()
+                .find(|src| !src.is_empty() && !is_badge(src))
+                .map(str::to_string)
+        });
+        if from_readme.is_some() {
+            return Ok(from_readme);
+        }
+
+        let root = list_dir(backend, repo, "").await?;
+        if let Some(url) = find_image(&root, ROOT_NAMES) {
+            return Ok(Some(url));
+        }
+        let has_docs = root
+            .iter()
+            .any(|e| e.kind == "dir" && e.name.eq_ignore_ascii_case("docs"));
+        if has_docs {
+            let docs = list_dir(backend, repo, "docs").await?;
+            return Ok(find_image(&docs, DOCS_NAMES));
+        }
+        Ok(None)
+    })
+    .await
+}
+
+pub fn contents_url(repo: &RepoKey, dir: &str) -> String {
+    format!(
+        "https://api.github.com/repos/{}/contents/{}",
+        repo.slug(),
+        dir
+    )
+}
+
+/// One entry of `GET /repos/{owner}/{repo}/contents/{dir}`.
+#[derive(Debug, Deserialize)]
+struct DirEntry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    download_url: Option<String>,
+}
+
+async fn list_dir(
+    backend: Backend<'_>,
+    repo: &RepoKey,
+    dir: &str,
+) -> Result<Vec<DirEntry>, FetchError> {
+    details::limited(backend, || async {
+        let request = HttpRequest::get(&contents_url(repo, dir))
+            .header("Accept", "application/vnd.github.v3+json");
+        match github::send(backend, request, None).await {
+            // Empty repos have no contents at all
+            Err(FetchError::Http { status: 404 }) => Ok(Vec::new()),
+            result => decode(&result?.body),
+        }
+    })
+    .await
+}
+
+/// The first file named like `names` (in that order of preference) with an image extension.
+fn find_image(entries: &[DirEntry], names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        IMAGE_EXTENSIONS.iter().find_map(|ext| {
+            let wanted = format!("{}.{}", name, ext);
+            entries
+                .iter()
+                .find(|e| e.kind == "file" && e.name.eq_ignore_ascii_case(&wanted))
+                .and_then(|e| e.download_url.clone())
+        })
+    })
+}
+
+/// CI, coverage and version badges lead many READMEs but make poor card images.
+fn is_badge(src: &str) -> bool {
+    let src = src.to_ascii_lowercase();
+    let path = src.split(['?', '#']).next().unwrap_or(&src);
+    path.ends_with(".svg")
+        || [
+            "shields.io",
+            "badgen.net",
+            "badge",
+            "codecov.io",
+            "travis-ci",
+            "/workflows/",
+        ]
+        .iter()
+        .any(|marker| src.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, kind: &str) -> DirEntry {
+        DirEntry {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            download_url: Some(format!("https://raw/{}", name)),
+        }
+    }
+
+    #[test]
+    fn overrides_match_by_key() {
+        let senet = RepoKey::new("github", "AZUREE0/senet");
+        assert!(manual_screenshot(&senet).is_some());
+        assert_eq!(
+            manual_screenshot(&RepoKey::new("github", "someone-else/Senet")),
+            None
+        );
+        assert_eq!(
+            manual_screenshot(&RepoKey::new("gitlab", "azuree0/Senet")),
+            None
+        );
+    }
+
+    #[test]
+    fn convention_files_in_order_of_preference() {
+        let entries = [
+            entry("preview.png", "file"),
+            entry("Screenshot.JPG", "file"),
+            entry("screenshot.txt", "file"),
+            entry("screenshot.png", "dir"),
+        ];
+        assert_eq!(
+            find_image(&entries, ROOT_NAMES).as_deref(),
+            Some("https://raw/Screenshot.JPG")
+        );
+        assert_eq!(
+            find_image(&entries, DOCS_NAMES).as_deref(),
+            Some("https://raw/preview.png")
+        );
+        assert_eq!(find_image(&entries[2..], ROOT_NAMES), None);
+    }
+
+    #[test]
+    fn badges_are_skipped() {
+        assert!(is_badge("https://img.shields.io/crates/v/x"));
+        assert!(is_badge(
+            "https://github.com/o/r/actions/workflows/ci.yml/badge.svg?branch=main"
+        ));
+        assert!(is_badge("https://raw/o/r/main/logo.svg"));
+        assert!(!is_badge("https://raw/o/r/main/docs/board.png"));
+    }
+}
@@ -0,0 +1,92 @@
+// Key-value persistence for caches: localStorage in the browser, a HashMap in tests
+use futures::future::{self, LocalBoxFuture};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// String-valued key-value store. Writes are best-effort (quota, private browsing).
+pub trait KeyValueStore {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&self, key: &str, value: &str);
+    fn remove(&self, key: &str);
+}
+
+/// Asynchronous key-value store for payloads too large for localStorage (IndexedDB).
+///
+/// Every [`KeyValueStore`] is also an `AsyncStore` that completes immediately.
+pub trait AsyncStore {
+    fn load<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Option<String>>;
+    fn save<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, ()>;
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, ()>;
+}
+
+impl<S: KeyValueStore + ?Sized> AsyncStore for S {
+    fn load<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, Option<String>> {
+        Box::pin(future::ready(self.get(key)))
+    }
+
+    fn save<'a>(&'a self, key: &'a str, value: &'a str) -> LocalBoxFuture<'a, ()> {
+        self.set(key, value);
+        Box::pin(future::ready(()))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> LocalBoxFuture<'a, ()> {
+        self.remove(key);
+        Box::pin(future::ready(()))
+    }
+}
+
+/// `window.localStorage`; behaves as empty when storage is unavailable.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalStore;
+
+impl LocalStore {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+}
+
+impl KeyValueStore for LocalStore {
+    fn get(&self, key: &str) -> Option<String> {
+        Self::storage()?.get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        if let Some(storage) = Self::storage() {
+            let _ = storage.set_item(key, value);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(storage) = Self::storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+}
+
+/// In-memory store for tests and host builds.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: RefCell<HashMap<String, String>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyValueStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.entries
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.borrow_mut().remove(key);
+    }
+}
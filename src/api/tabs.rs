@@ -0,0 +1,180 @@
+// Cross-tab coordination: one tab talks to GitHub, the others follow its broadcasts
+use futures::channel::oneshot;
+use futures::future;
+use gloo_events::EventListener;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{BroadcastChannel, MessageEvent, StorageEvent};
+
+const LOCK_KEY: &str = "portfolio_fetch_lock";
+const CHANNEL_NAME: &str = "portfolio_repos";
+/// Written on every refresh where `BroadcastChannel` is missing, so other tabs get a
+/// `storage` event (the repo cache itself lives in IndexedDB, which fires none).
+const UPDATED_KEY: &str = "portfolio_repos_updated";
+/// Long enough to cover a paginated fetch; a crashed leader frees the lock after this.
+const LOCK_TTL_MS: f64 = 30_000.0;
+
+thread_local! {
+    static TAB_ID: String = format!("{:08x}", (js_sys::Math::random() * u32::MAX as f64) as u32);
+}
+
+fn tab_id() -> String {
+    TAB_ID.with(Clone::clone)
+}
+
+#[derive(Serialize, Deserialize)]
+struct FetchLock {
+    owner: String,
+    expires_at: f64,
+}
+
+/// Held while this tab talks to GitHub; dropping the last one releases the lock, so every
+/// exit path (early return, superseded refresh) frees it.
+pub struct FetchLockGuard(());
+
+impl Drop for FetchLockGuard {
+    fn drop(&mut self) {
+        let last = HOLDERS.with(|holders| {
+            holders.set(holders.get() - 1);
+            holders.get() == 0
+        });
+        if last {
+            release_lock();
+        }
+    }
+}
+
+thread_local! {
+    /// Guards alive in this tab; overlapping refreshes here share one lock
+    static HOLDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Claims the right to hit the network. Returns `None` while another tab holds an unexpired
+/// lock.
+///
+/// localStorage has no compare-and-swap, so this re-reads after writing; two tabs racing
+/// in the same tick may both win, which only costs one extra request.
+pub fn try_acquire_lock() -> Option<FetchLockGuard> {
+    let me = tab_id();
+    let now = js_sys::Date::now();
+    if let Ok(lock) = LocalStorage::get::<FetchLock>(LOCK_KEY) {
+        if lock.owner != me && lock.expires_at > now {
+            return None;
+        }
+    }
+    let lock = FetchLock {
+        owner: me.clone(),
+        expires_at: now + LOCK_TTL_MS,
+    };
+    // Storage unavailable: nothing to coordinate through
+    let won = LocalStorage::set(LOCK_KEY, lock).is_err()
+        || LocalStorage::get::<FetchLock>(LOCK_KEY).map_or(true, |l| l.owner == me);
+    won.then(|| {
+        HOLDERS.with(|holders| holders.set(holders.get() + 1));
+        FetchLockGuard(())
+    })
+}
+
+/// Releases the lock if this tab holds it.
+fn release_lock() {
+    if let Ok(lock) = LocalStorage::get::<FetchLock>(LOCK_KEY) {
+        if lock.owner == tab_id() {
+            LocalStorage::delete(LOCK_KEY);
+        }
+    }
+}
+
+/// Resolves when another tab announces a refresh or releases the fetch lock, whether its
+/// fetch succeeded or not, or once the lock would have lapsed (the tab may have closed
+/// mid-fetch).
+pub async fn next_update() {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Rc::new(RefCell::new(Some(sender)));
+    let notify = move || {
+        if let Some(sender) = sender.borrow_mut().take() {
+            let _ = sender.send(());
+        }
+    };
+    let _subscription = subscribe(notify.clone());
+    let _released = on_lock_released(notify);
+    let timeout = gloo_timers::future::TimeoutFuture::new(LOCK_TTL_MS as u32);
+    future::select(receiver, timeout).await;
+}
+
+/// Calls `on_release` when another tab deletes the fetch lock. The lock lives in
+/// localStorage, so its removal reaches every other tab as a `storage` event.
+fn on_lock_released(on_release: impl Fn() + 'static) -> Option<EventListener> {
+    let window = web_sys::window()?;
+    Some(EventListener::new(&window, "storage", move |event| {
+        let released = event
+            .dyn_ref::<StorageEvent>()
+            .is_some_and(|e| e.key().as_deref() == Some(LOCK_KEY) && e.new_value().is_none());
+        if released {
+            on_release();
+        }
+    }))
+}
+
+/// Tells other tabs the shared repo cache was just refreshed.
+///
+/// Without `BroadcastChannel`, bumps [`UPDATED_KEY`] so other tabs see a `storage` event.
+pub fn announce_update() {
+    match BroadcastChannel::new(CHANNEL_NAME) {
+        Ok(channel) => {
+            let _ = channel.post_message(&JsValue::from_str(&tab_id()));
+            channel.close();
+        }
+        Err(_) => {
+            let _ = LocalStorage::set(UPDATED_KEY, js_sys::Date::now());
+        }
+    }
+}
+
+/// Keeps a cross-tab subscription alive; drop to unsubscribe.
+pub struct TabSubscription {
+    channel: Option<BroadcastChannel>,
+    _listener: EventListener,
+}
+
+impl Drop for TabSubscription {
+    fn drop(&mut self) {
+        if let Some(ref channel) = self.channel {
+            channel.close();
+        }
+    }
+}
+
+/// Calls `on_update` whenever another tab refreshes the shared repo cache.
+pub fn subscribe(on_update: impl Fn() + 'static) -> Option<TabSubscription> {
+    if let Ok(channel) = BroadcastChannel::new(CHANNEL_NAME) {
+        let me = tab_id();
+        let listener = EventListener::new(&channel, "message", move |event| {
+            let from = event
+                .dyn_ref::<MessageEvent>()
+                .and_then(|e| e.data().as_string());
+            if from.as_deref() != Some(me.as_str()) {
+                on_update();
+            }
+        });
+        return Some(TabSubscription {
+            channel: Some(channel),
+            _listener: listener,
+        });
+    }
+
+    // Fallback: `storage` fires in every other tab sharing this origin
+    let window = web_sys::window()?;
+    let listener = EventListener::new(&window, "storage", move |event| {
+        let key = event.dyn_ref::<StorageEvent>().and_then(|e| e.key());
+        if key.as_deref() == Some(UPDATED_KEY) {
+            on_update();
+        }
+    });
+    Some(TabSubscription {
+        channel: None,
+        _listener: listener,
+    })
+}
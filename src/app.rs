@@ -1,8 +1,9 @@
-use crate::api::github;
-use crate::components::{Hero, MarineSnow, RepoGrid};
+use crate::api::github::{self, FetchOptions};
+use crate::api::store::LocalStore;
+use crate::api::{rate_limit, tabs, DataSource};
+use crate::components::{ActivityHeatmap, DataStatus, Hero, LanguagePanel, MarineSnow, RepoGrid};
+use crate::hooks::{use_refresh_scheduler, RefreshConfig};
 use crate::scene::Scene3d;
-use gloo_events::EventListener;
-use gloo_timers::callback::Interval;
 use std::rc::Rc;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::window;
@@ -10,58 +11,110 @@ use yew::prelude::*;
 
 const EMAIL: &str = "azure.ad@yahoo.com";
 
+/// Repo fetches still running; mount, scheduled and manual refreshes can overlap.
+#[derive(Default, PartialEq)]
+struct InFlight(u32);
+
+enum Fetch {
+    Started,
+    Finished,
+}
+
+impl Reducible for InFlight {
+    type Action = Fetch;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        Rc::new(Self(match action {
+            Fetch::Started => self.0 + 1,
+            Fetch::Finished => self.0.saturating_sub(1),
+        }))
+    }
+}
+
 #[function_component(App)]
 pub fn app() -> Html {
-    // Start with fallback so content shows immediately; fetch updates in background
-    let repos = use_state(|| github::static_fallback());
+    // Start with fallback so content shows immediately; fetch updates in background.
+    // `use_state_eq` so re-reporting the same cached list does not re-render RepoGrid.
+    let repos = use_state_eq(github::static_fallback);
+    let source = use_state(|| None::<DataSource>);
+    let in_flight = use_reducer_eq(InFlight::default);
+    let rate_limit = use_state(|| rate_limit::load(&LocalStore));
     let tagline_hovered = use_state(|| false);
 
+    // `bypass_ttl` is set by the manual refresh action only
     let fetch_repos = Rc::new({
         let repos = repos.clone();
-        move || {
+        let source = source.clone();
+        let in_flight = in_flight.dispatcher();
+        let rate_limit = rate_limit.clone();
+        move |bypass_ttl: bool| {
             let repos = repos.clone();
+            let source = source.clone();
+            let in_flight = in_flight.clone();
+            let rate_limit = rate_limit.clone();
+            in_flight.dispatch(Fetch::Started);
             wasm_bindgen_futures::spawn_local(async move {
-                let data = github::fetch_repos_with_fallback().await;
-                repos.set(data);
+                let options = FetchOptions {
+                    bypass_ttl,
+                    ..FetchOptions::default()
+                };
+                github::fetch_repos_swr(&options, |update| {
+                    if let DataSource::StaleCache { ref error, .. }
+                    | DataSource::StaticFallback { ref error } = update.source
+                    {
+                        web_sys::console::warn_1(&format!("Repo fetch failed: {}", error).into());
+                    }
+                    if let Some(ref error) = update.cache_error {
+                        web_sys::console::warn_1(
+                            &format!("Repo cache discarded: {}", error).into(),
+                        );
+                    }
+                    // Unchanged revalidations leave `repos` alone so RepoGrid does not re-render
+                    if let Some(data) = update.repos {
+                        repos.set(data);
+                    }
+                    source.set(Some(update.source));
+                })
+                .await;
+                in_flight.dispatch(Fetch::Finished);
+                rate_limit.set(rate_limit::load(&LocalStore));
             });
         }
     });
 
-    // Initial fetch (runs in background; fallback already shown)
+    // Follow refreshes made by other tabs (only one tab talks to GitHub at a time)
     {
-        let fetch_repos = fetch_repos.clone();
+        let repos = repos.clone();
+        let source = source.clone();
         use_effect_with((), move |_| {
-            fetch_repos();
-            || ()
+            let subscription = tabs::subscribe(move || {
+                let repos = repos.clone();
+                let source = source.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Some(data) = github::cached_repos().await {
+                        repos.set(data);
+                        source.set(Some(github::BROADCAST_SOURCE));
+                    }
+                });
+            });
+            move || drop(subscription)
         });
     }
 
-    // Tab focus refresh
+    // Mount fetch (fallback already shown), periodic refresh while visible, catch-up on return.
+    // While GitHub's rate limit is in effect the fetch path serves the cache without the network.
     {
         let fetch_repos = fetch_repos.clone();
-        use_effect_with((), move |_| {
-            let document = window().and_then(|w| w.document()).expect("no document");
-            let doc_clone = document.clone();
-            let listener = EventListener::new(&document, "visibilitychange", move |_| {
-                if doc_clone.visibility_state() == web_sys::VisibilityState::Visible {
-                    fetch_repos();
-                }
-            });
-            move || drop(listener)
-        });
+        use_refresh_scheduler(
+            RefreshConfig::default(),
+            Callback::from(move |_| fetch_repos(false)),
+        );
     }
 
-    // Periodic refresh every 30 min
-    {
+    let refresh_now = {
         let fetch_repos = fetch_repos.clone();
-        use_effect_with((), move |_| {
-            let fetch = fetch_repos.clone();
-            let _interval = Interval::new(30 * 60 * 1000, move || {
-                fetch();
-            });
-            || ()
-        });
-    }
+        Callback::from(move |_: MouseEvent| fetch_repos(true))
+    };
 
     let copy_email = Callback::from(move |_: MouseEvent| {
         if let Some(w) = window() {
@@ -91,13 +144,21 @@ pub fn app() -> Html {
                             move |_| tagline_hovered.set(false)
                         })}
                     />
+                    <ActivityHeatmap />
                     <main class="content">
+                        <LanguagePanel repos={(*repos).clone()} />
                         <RepoGrid repos={(*repos).clone()} show_poem={*tagline_hovered} />
                     </main>
                     <footer class="overlay-footer">
                         <button type="button" class="hero-email" onclick={copy_email} title="Copy email">
                             {EMAIL}
                         </button>
+                        <DataStatus
+                            source={(*source).clone()}
+                            refreshing={in_flight.0 > 0}
+                            on_refresh={refresh_now}
+                            rate_limit={(*rate_limit).clone()}
+                        />
                     </footer>
                 </div>
             </div>
@@ -0,0 +1,144 @@
+use crate::api::clock::{Clock, SystemClock};
+use crate::api::events::{self, FEED_DAYS};
+use crate::models::activity::{self, calendar, day_start, level, weekday};
+use crate::models::{Activity, Timestamp};
+use yew::prelude::*;
+
+/// Cell size and spacing in SVG user units.
+const CELL: usize = 10;
+const GAP: usize = 3;
+/// Room for the month labels above and the weekday labels to the left.
+const TOP: usize = 14;
+const LEFT: usize = 26;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Contribution-style calendar of public events over the last [`FEED_DAYS`], all the feed
+/// serves, one cell per day, shaded by how busy the day was. Hidden until the events
+/// arrive, and for good if they cannot be fetched.
+#[function_component(ActivityHeatmap)]
+pub fn activity_heatmap() -> Html {
+    let activity = use_state_eq(|| None::<Activity>);
+    {
+        let activity = activity.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match events::fetch_activity().await {
+                    Ok(fetched) => activity.set(Some(fetched)),
+                    Err(e) => {
+                        web_sys::console::warn_1(&format!("Activity fetch failed: {}", e).into())
+                    }
+                }
+            });
+        });
+    }
+    let Some(ref activity) = *activity else {
+        return Html::default();
+    };
+
+    let today = activity::day_number(Timestamp::from_unix_ms(SystemClock.now_ms() as i64));
+    let first = today - (i64::from(FEED_DAYS) - 1);
+    // Partial weeks at both ends: 13 or 14 columns
+    let columns = (weekday(first) + FEED_DAYS as usize).div_ceil(7);
+    let weeks = calendar(today, columns);
+    let busiest = activity.busiest();
+    let pitch = CELL + GAP;
+    let width = LEFT + columns * pitch - GAP;
+    let height = TOP + 7 * pitch - GAP;
+
+    let cells = weeks.iter().enumerate().flat_map(|(column, week)| {
+        week.iter()
+            .enumerate()
+            .filter(|(_, day)| (first..=today).contains(*day))
+            .map(move |(row, &day)| {
+                let counts = activity.day(day);
+                let date = &day_start(day).to_iso()[..10];
+                let tooltip = match counts.total() {
+                    0 => format!("{}: no public activity", date),
+                    1 => format!("{}: 1 event ({})", date, counts.breakdown()),
+                    n => format!("{}: {} events ({})", date, n, counts.breakdown()),
+                };
+                html! {
+                    <rect
+                        x={(LEFT + column * pitch).to_string()}
+                        y={(TOP + row * pitch).to_string()}
+                        width={CELL.to_string()}
+                        height={CELL.to_string()}
+                        rx="2"
+                        class={format!("heatmap-cell heatmap-level-{}", level(counts.total(), busiest))}
+                    >
+                        <title>{tooltip}</title>
+                    </rect>
+                }
+            })
+    });
+
+    // A month label over the first column whose Sunday falls in that month
+    let month_labels = weeks.iter().enumerate().filter_map(|(column, week)| {
+        let month = month_of(week[0]);
+        let starts_month = column == 0 || month_of(weeks[column - 1][0]) != month;
+        // The first column is usually mid-month and would crowd the next label
+        (starts_month && column + 2 < columns && (column > 0 || month_of(week[6]) == month)).then(
+            || {
+                html! {
+                    <text x={(LEFT + column * pitch).to_string()} y="9" class="heatmap-label">
+                        {MONTHS[month]}
+                    </text>
+                }
+            },
+        )
+    });
+
+    let day_labels = [(1, "Mon"), (3, "Wed"), (5, "Fri")]
+        .into_iter()
+        .map(|(row, name)| {
+            html! {
+                <text x="0" y={(TOP + row * pitch + CELL - 1).to_string()} class="heatmap-label">
+                    {name}
+                </text>
+            }
+        });
+
+    let total = activity.total();
+    let summary = match total {
+        1 => format!("1 public event in the last {} days", FEED_DAYS),
+        n => format!("{} public events in the last {} days", n, FEED_DAYS),
+    };
+    html! {
+        <section class="activity-heatmap" aria-label={format!("Public activity over the last {} days", FEED_DAYS)}>
+            <div class="activity-heatmap-scroll">
+                <svg
+                    class="heatmap"
+                    viewBox={format!("0 0 {} {}", width, height)}
+                    width={width.to_string()}
+                    height={height.to_string()}
+                    role="img"
+                    aria-label={summary.clone()}
+                >
+                    { for month_labels }
+                    { for day_labels }
+                    { for cells }
+                </svg>
+            </div>
+            <div class="activity-heatmap-footer">
+                <span>{summary}</span>
+                <span class="heatmap-legend" aria-hidden="true">
+                    {"Less"}
+                    { for (0..=4).map(|level| html! {
+                        <span class={format!("heatmap-swatch heatmap-level-{}", level)} />
+                    }) }
+                    {"More"}
+                </span>
+            </div>
+        </section>
+    }
+}
+
+/// 0-based month of `day`.
+fn month_of(day: i64) -> usize {
+    day_start(day).to_iso()[5..7]
+        .parse::<usize>()
+        .map_or(0, |month| month - 1)
+}
@@ -0,0 +1,143 @@
+// Footer indicator: where the repo grid data came from, how old it is and the API quota left
+use crate::api::clock::{Clock, SystemClock};
+use crate::api::rate_limit::RateLimit;
+use crate::api::DataSource;
+use gloo_timers::callback::{Interval, Timeout};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct DataStatusProps {
+    /// `None` until the first fetch completes
+    pub source: Option<DataSource>,
+    pub refreshing: bool,
+    pub on_refresh: Callback<MouseEvent>,
+    /// Last known GitHub quota
+    #[prop_or_default]
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[function_component(DataStatus)]
+pub fn data_status(props: &DataStatusProps) -> Html {
+    // Re-render every minute so "cached N min ago" stays accurate
+    let tick = use_state(|| 0u32);
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |_| {
+            let interval = Interval::new(60 * 1000, move || tick.set(tick.wrapping_add(1)));
+            move || drop(interval)
+        });
+    }
+
+    // Re-render when the block lifts or the quota window resets, so the note does not linger
+    {
+        let tick = tick.clone();
+        use_effect_with((props.rate_limit.clone(), *tick), move |(limit, _)| {
+            let now = SystemClock.now_secs();
+            let next = limit
+                .as_ref()
+                .into_iter()
+                .flat_map(|l| [l.blocked_until, l.reset_at])
+                .flatten()
+                .filter(|&t| t > now)
+                .min();
+            let timeout = next.map(|t| {
+                let delay_ms = ((t - now) * 1000 + 500).min(u32::MAX as u64) as u32;
+                Timeout::new(delay_ms, move || tick.set(tick.wrapping_add(1)))
+            });
+            move || drop(timeout)
+        });
+    }
+
+    let (class, label, title) = match props.source {
+        None => ("data-status", "loading…".to_string(), None),
+        Some(DataSource::Fresh) | Some(DataSource::Revalidated) => {
+            ("data-status live", "live".to_string(), None)
+        }
+        Some(DataSource::Cached { fetched_at }) => (
+            "data-status cached",
+            format!("cached {}", format_age(fetched_at)),
+            None,
+        ),
+        Some(DataSource::Expired { fetched_at }) => (
+            "data-status stale",
+            format!("cached {}, updating", format_age(fetched_at)),
+            Some("Past its refresh interval; checking GitHub".to_string()),
+        ),
+        Some(DataSource::StaleCache {
+            fetched_at,
+            ref error,
+        }) => (
+            "data-status stale",
+            format!("cached {}", format_age(fetched_at)),
+            Some(error.to_string()),
+        ),
+        Some(DataSource::StaticFallback { ref error }) => (
+            "data-status fallback",
+            "offline placeholder".to_string(),
+            Some(error.to_string()),
+        ),
+    };
+
+    let now = SystemClock.now_secs();
+    let quota = props.rate_limit.as_ref().and_then(|limit| {
+        if limit.blocks_at(now) {
+            return Some(format!(
+                "GitHub API limit reached, resets {}",
+                format_time(limit.blocked_until)
+            ));
+        }
+        let window_open = limit.reset_at.is_some_and(|t| t > now);
+        match (limit.remaining, limit.limit) {
+            (Some(remaining), Some(total)) if window_open => Some(format!(
+                "{}/{} API requests left, resets {}",
+                remaining,
+                total,
+                format_time(limit.reset_at)
+            )),
+            _ => None,
+        }
+    });
+
+    html! {
+        <span class={class} title={title}>
+            <span class="data-status-dot" aria-hidden="true" />
+            <span class="data-status-label">{label}</span>
+            <button
+                type="button"
+                class="data-status-refresh"
+                onclick={props.on_refresh.clone()}
+                disabled={props.refreshing}
+                title="Refresh now"
+            >
+                {if props.refreshing { "refreshing…" } else { "refresh now" }}
+            </button>
+            if let Some(quota) = quota {
+                <span class="rate-limit-note">{quota}</span>
+            }
+        </span>
+    }
+}
+
+/// "just now", "12 min ago", "3 h ago", "2 days ago" for a Unix-ms timestamp
+fn format_age(fetched_at: u64) -> String {
+    let mins = SystemClock.now_ms().saturating_sub(fetched_at) / 60_000;
+    match mins {
+        0 => "just now".to_string(),
+        1..=59 => format!("{} min ago", mins),
+        60..=1439 => format!("{} h ago", mins / 60),
+        _ => match mins / 1440 {
+            1 => "1 day ago".to_string(),
+            days => format!("{} days ago", days),
+        },
+    }
+}
+
+/// Local wall-clock time for a Unix-seconds timestamp.
+fn format_time(secs: Option<u64>) -> String {
+    secs.map(|secs| {
+        js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(secs as f64 * 1000.0))
+            .to_locale_time_string("default")
+            .into()
+    })
+    .unwrap_or_else(|| "soon".to_string())
+}
@@ -0,0 +1,74 @@
+use crate::models::languages::OTHER;
+use crate::models::Languages;
+use yew::prelude::*;
+
+/// Languages under this share of the repo are grouped into "Other".
+const MIN_FRACTION: f64 = 0.02;
+
+#[derive(Properties, PartialEq)]
+pub struct LanguageBarProps {
+    pub languages: Languages,
+}
+
+/// Stacked bar of a repo's languages by bytes, with a tooltip per segment.
+#[function_component(LanguageBar)]
+pub fn language_bar(props: &LanguageBarProps) -> Html {
+    let shares = props.languages.shares(MIN_FRACTION);
+    if shares.is_empty() {
+        return html! {};
+    }
+    let summary = shares
+        .iter()
+        .map(|s| format!("{} {:.1}%", s.name, s.fraction * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+        <div class="language-bar" role="img" aria-label={summary}>
+            { for shares.iter().map(|share| html! {
+                <span
+                    class="language-bar-segment"
+                    style={format!(
+                        "width: {:.2}%; background: {}",
+                        share.fraction * 100.0,
+                        language_color(&share.name)
+                    )}
+                    title={format!("{} {:.1}%", share.name, share.fraction * 100.0)}
+                />
+            }) }
+        </div>
+    }
+}
+
+/// Theme colour for the languages this portfolio is mostly written in.
+pub fn theme_color(language: &str) -> Option<&'static str> {
+    match language {
+        "Rust" => Some("var(--accent-cyan)"),
+        "C++" => Some("var(--accent-aqua)"),
+        "Python" => Some("var(--accent-green)"),
+        "Liquid" => Some("var(--accent-light)"),
+        _ => None,
+    }
+}
+
+/// Theme colour if there is one, otherwise a hue derived from the name (stable across
+/// renders and repos), kept in the blue-green range of the page.
+pub fn language_color(language: &str) -> String {
+    if language == OTHER {
+        return "var(--text-muted)".to_string();
+    }
+    if let Some(color) = theme_color(language) {
+        return color.to_string();
+    }
+    let hash = fnv1a(language);
+    let hue = 150 + hash % 130;
+    let lightness = 45 + (hash >> 8) % 25;
+    format!("hsl({}, 70%, {}%)", hue, lightness)
+}
+
+/// 32-bit FNV-1a: tiny, and spreads similar names apart.
+pub(crate) fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5_u32, |h, b| {
+        (h ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
+}
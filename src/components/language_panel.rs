@@ -0,0 +1,233 @@
+use crate::api::languages;
+use crate::components::language_bar::language_color;
+use crate::models::languages::{aggregate, LanguageTotal, OTHER};
+use crate::models::{Languages, Repo, RepoKey};
+use futures::future;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Donut geometry in SVG user units, inside a 120 × 120 viewBox.
+const RADIUS: f64 = 46.0;
+const STROKE: f64 = 18.0;
+/// Rows in the chart and legend; the remaining languages are summed into "Other".
+const MAX_ROWS: usize = 8;
+
+#[derive(Properties, PartialEq)]
+pub struct LanguagePanelProps {
+    pub repos: Vec<Repo>,
+}
+
+/// How one repo's lookup ended.
+#[derive(Clone, PartialEq)]
+enum Lookup {
+    /// Possibly empty, for a repo without code
+    Found(Languages),
+    /// Tried again the next time the panel loads
+    Failed,
+}
+
+/// Lookups finished so far, by repo.
+#[derive(Default, PartialEq)]
+struct Loaded(HashMap<RepoKey, Lookup>);
+
+impl Reducible for Loaded {
+    type Action = (RepoKey, Lookup);
+
+    fn reduce(self: Rc<Self>, (key, lookup): Self::Action) -> Rc<Self> {
+        let mut by_repo = self.0.clone();
+        by_repo.insert(key, lookup);
+        Rc::new(Self(by_repo))
+    }
+}
+
+/// Collapsible profile-wide language breakdown: a donut of bytes per language across all
+/// repos, with repo counts in the legend.
+///
+/// Language data is only requested once the panel is opened, through the detail limiter
+/// like any other per-repo lookup, and is shared with the per-card bars through the cache.
+#[function_component(LanguagePanel)]
+pub fn language_panel(props: &LanguagePanelProps) -> Html {
+    let expanded = use_state_eq(|| false);
+    let loaded = use_reducer_eq(Loaded::default);
+    let keys = github_keys(&props.repos);
+
+    {
+        let loaded = loaded.clone();
+        use_effect_with(
+            (*expanded, props.repos.clone()),
+            move |(expanded, repos)| {
+                let mut abort = None;
+                if *expanded {
+                    // Languages already found are kept; failed lookups are tried again
+                    let dispatcher = loaded.dispatcher();
+                    let lookups = github_keys(repos)
+                        .into_iter()
+                        .filter(|key| !matches!(loaded.0.get(key), Some(Lookup::Found(_))))
+                        .map(move |key| {
+                            let dispatcher = dispatcher.clone();
+                            async move {
+                                let lookup = match languages::fetch_languages(&key).await {
+                                    Ok(languages) => Lookup::Found(languages),
+                                    Err(_) => Lookup::Failed,
+                                };
+                                dispatcher.dispatch((key, lookup));
+                            }
+                        });
+                    let (all, handle) = future::abortable(future::join_all(lookups));
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let _ = all.await;
+                    });
+                    abort = Some(handle);
+                }
+                // Collapsing or a new repo list drops the lookups still waiting for a slot
+                move || abort.into_iter().for_each(|handle| handle.abort())
+            },
+        );
+    }
+
+    let toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    let finished = keys.iter().filter(|k| loaded.0.contains_key(*k)).count();
+    let available: Vec<&Languages> = keys
+        .iter()
+        .filter_map(|k| match loaded.0.get(k)? {
+            Lookup::Found(languages) => Some(languages),
+            Lookup::Failed => None,
+        })
+        .collect();
+    let totals = aggregate(available.iter().copied());
+    let language_count = totals.len();
+    let rows = top_rows(totals, &available);
+    let total_bytes: u64 = rows.iter().map(|r| r.bytes).sum();
+
+    html! {
+        <section class="language-panel">
+            <button
+                type="button"
+                class="language-panel-toggle"
+                aria-expanded={expanded.to_string()}
+                onclick={toggle}
+            >
+                <span>{"Languages"}</span>
+                <span class="language-panel-chevron" aria-hidden="true">{"▾"}</span>
+            </button>
+            if *expanded {
+                if rows.is_empty() && finished < keys.len() {
+                    <p class="language-panel-note">{"Loading language data…"}</p>
+                } else if rows.is_empty() {
+                    <p class="language-panel-note">{"Language data is unavailable right now."}</p>
+                } else {
+                    <div class="language-panel-body">
+                        {donut(&rows, total_bytes, language_count)}
+                        <ul class="language-legend">
+                            { for rows.iter().map(|row| legend_row(row, total_bytes)) }
+                        </ul>
+                    </div>
+                    if available.len() < keys.len() {
+                        <p class="language-panel-note">
+                            {format!("Based on {} of {} repos", available.len(), keys.len())}
+                        </p>
+                    }
+                }
+            }
+        </section>
+    }
+}
+
+/// Repos whose languages can be looked up on GitHub.
+fn github_keys(repos: &[Repo]) -> Vec<RepoKey> {
+    repos
+        .iter()
+        .map(Repo::key)
+        .filter(|key| key.provider() == "github")
+        .collect()
+}
+
+/// The largest [`MAX_ROWS`] − 1 languages, plus one "Other" row for the rest.
+fn top_rows(mut totals: Vec<LanguageTotal>, per_repo: &[&Languages]) -> Vec<LanguageTotal> {
+    if totals.len() <= MAX_ROWS {
+        return totals;
+    }
+    let rest = totals.split_off(MAX_ROWS - 1);
+    let names: Vec<&str> = rest.iter().map(|t| t.name.as_str()).collect();
+    totals.push(LanguageTotal {
+        name: OTHER.to_string(),
+        bytes: rest.iter().map(|t| t.bytes).sum(),
+        // Summing per-language counts would count a repo once per minor language
+        repos: per_repo
+            .iter()
+            .filter(|langs| names.iter().any(|name| langs.bytes(name) > 0))
+            .count(),
+    });
+    totals
+}
+
+/// Ring of arcs drawn as dashed circle strokes, starting at 12 o'clock.
+fn donut(rows: &[LanguageTotal], total_bytes: u64, language_count: usize) -> Html {
+    let circumference = 2.0 * PI * RADIUS;
+    let mut offset = 0.0;
+    let arcs = rows.iter().map(|row| {
+        let length = circumference * row.bytes as f64 / total_bytes as f64;
+        let arc = html! {
+            <circle
+                cx="60"
+                cy="60"
+                r={RADIUS.to_string()}
+                fill="none"
+                stroke={language_color(&row.name)}
+                stroke-width={STROKE.to_string()}
+                stroke-dasharray={format!("{:.3} {:.3}", length, circumference - length)}
+                stroke-dashoffset={format!("{:.3}", -offset)}
+                transform="rotate(-90 60 60)"
+            >
+                <title>{format!("{}: {}", row.name, percent(row.bytes, total_bytes))}</title>
+            </circle>
+        };
+        offset += length;
+        arc
+    });
+    let languages = if language_count == 1 {
+        "language"
+    } else {
+        "languages"
+    };
+
+    html! {
+        <svg class="language-donut" viewBox="0 0 120 120" role="img" aria-label="Bytes of code per language">
+            { for arcs }
+            <text x="60" y="58" text-anchor="middle" class="language-donut-count">
+                {language_count}
+            </text>
+            <text x="60" y="72" text-anchor="middle" class="language-donut-label">
+                {languages}
+            </text>
+        </svg>
+    }
+}
+
+fn legend_row(row: &LanguageTotal, total_bytes: u64) -> Html {
+    let repos = match row.repos {
+        1 => "1 repo".to_string(),
+        n => format!("{} repos", n),
+    };
+    html! {
+        <li class="language-legend-row">
+            <span
+                class="language-legend-swatch"
+                style={format!("background: {}", language_color(&row.name))}
+            />
+            <span class="language-legend-name">{&row.name}</span>
+            <span class="language-legend-share">{percent(row.bytes, total_bytes)}</span>
+            <span class="language-legend-repos">{repos}</span>
+        </li>
+    }
+}
+
+fn percent(bytes: u64, total: u64) -> String {
+    format!("{:.1}%", bytes as f64 * 100.0 / total as f64)
+}
@@ -0,0 +1,142 @@
+use crate::markdown::{Align, Block, Document, Inline, ListItem};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct MarkdownProps {
+    pub document: Document,
+}
+
+/// Renders a parsed Markdown document node by node; no markup from the source is ever
+/// injected as HTML.
+#[function_component(Markdown)]
+pub fn markdown(props: &MarkdownProps) -> Html {
+    html! {
+        <div class="markdown">
+            { for props.document.blocks.iter().map(|b| block(b, false)) }
+        </div>
+    }
+}
+
+/// `tight` list items show their paragraphs without `<p>` spacing.
+fn block(node: &Block, tight: bool) -> Html {
+    match node {
+        Block::Heading { level, content } => {
+            // Nested inside a card below its `h3` name, so `#` starts at `h4`
+            let tag = format!("h{}", (level + 3).min(6));
+            html! {
+                <@{tag} class={format!("md-h{}", level)}>{inlines(content)}</@>
+            }
+        }
+        Block::Paragraph(content) if tight => inlines(content),
+        Block::Paragraph(content) => html! { <p>{inlines(content)}</p> },
+        Block::Code { lang, text } => html! {
+            <pre class="md-code">
+                <code class={lang.as_ref().map(|lang| format!("language-{}", lang))}>{text}</code>
+            </pre>
+        },
+        Block::Quote(blocks) => html! {
+            <blockquote>{ for blocks.iter().map(|b| block(b, false)) }</blockquote>
+        },
+        Block::List {
+            start: None,
+            tight,
+            items,
+        } => html! {
+            <ul>{ for items.iter().map(|item| list_item(item, *tight)) }</ul>
+        },
+        Block::List {
+            start: Some(start),
+            tight,
+            items,
+        } => html! {
+            <ol start={start.to_string()}>
+                { for items.iter().map(|item| list_item(item, *tight)) }
+            </ol>
+        },
+        Block::Table { align, head, rows } => {
+            let style = |column: usize| match align.get(column) {
+                Some(Align::Left) => Some("text-align: left"),
+                Some(Align::Center) => Some("text-align: center"),
+                Some(Align::Right) => Some("text-align: right"),
+                Some(Align::None) | None => None,
+            };
+            html! {
+                <div class="md-table-wrap">
+                    <table class="md-table">
+                        <thead>
+                            <tr>
+                                { for head.iter().enumerate().map(|(i, cell)| html! {
+                                    <th style={style(i)}>{inlines(cell)}</th>
+                                }) }
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for rows.iter().map(|row| html! {
+                                <tr>
+                                    { for row.iter().enumerate().map(|(i, cell)| html! {
+                                        <td style={style(i)}>{inlines(cell)}</td>
+                                    }) }
+                                </tr>
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        }
+        Block::Rule => html! { <hr /> },
+    }
+}
+
+fn list_item(item: &ListItem, tight: bool) -> Html {
+    let blocks = item.blocks.iter().map(|b| block(b, tight));
+    match item.checked {
+        Some(checked) => html! {
+            <li class="md-task">
+                <input type="checkbox" {checked} disabled=true />
+                { for blocks }
+            </li>
+        },
+        None => html! { <li>{ for blocks }</li> },
+    }
+}
+
+fn inlines(content: &[Inline]) -> Html {
+    content.iter().map(inline).collect()
+}
+
+fn inline(node: &Inline) -> Html {
+    match node {
+        Inline::Text(text) => html! { {text} },
+        Inline::Code(code) => html! { <code>{code}</code> },
+        Inline::Emphasis(content) => html! { <em>{inlines(content)}</em> },
+        Inline::Strong(content) => html! { <strong>{inlines(content)}</strong> },
+        Inline::Strikethrough(content) => html! { <del>{inlines(content)}</del> },
+        // Dropped as unsafe when the document was built
+        Inline::Link { href, content, .. } if href.is_empty() => inlines(content),
+        Inline::Link {
+            href,
+            title,
+            content,
+        } => html! {
+            <a
+                href={href.clone()}
+                title={title.clone()}
+                target="_blank"
+                rel="noopener noreferrer nofollow"
+            >
+                {inlines(content)}
+            </a>
+        },
+        Inline::Image { src, alt, .. } if src.is_empty() => html! { {alt} },
+        Inline::Image { src, alt, title } => html! {
+            <img
+                src={src.clone()}
+                alt={alt.clone()}
+                title={title.clone()}
+                loading="lazy"
+                referrerpolicy="no-referrer"
+            />
+        },
+        Inline::LineBreak => html! { <br /> },
+    }
+}
@@ -1,9 +1,27 @@
+mod activity_heatmap;
+mod data_status;
 mod hero;
+mod language_bar;
+mod language_panel;
 mod marine_snow;
+mod markdown;
+mod readme_panel;
 mod repo_card;
 mod repo_grid;
+mod screenshot_placeholder;
+mod sparkline;
+mod time_ago;
 
+pub use activity_heatmap::ActivityHeatmap;
+pub use data_status::DataStatus;
 pub use hero::Hero;
+pub use language_bar::LanguageBar;
+pub use language_panel::LanguagePanel;
 pub use marine_snow::MarineSnow;
+pub use markdown::Markdown;
+pub use readme_panel::ReadmePanel;
 pub use repo_card::RepoCard;
 pub use repo_grid::RepoGrid;
+pub use screenshot_placeholder::ScreenshotPlaceholder;
+pub use sparkline::Sparkline;
+pub use time_ago::TimeAgo;
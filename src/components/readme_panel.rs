@@ -0,0 +1,43 @@
+use crate::api::readme;
+use crate::components::Markdown;
+use crate::hooks::use_repo_detail;
+use crate::models::RepoKey;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ReadmePanelProps {
+    pub repo: RepoKey,
+    /// Where to read the repo on GitHub when its README cannot be shown
+    pub html_url: AttrValue,
+}
+
+/// A repo's README, rendered below its card once opened.
+#[function_component(ReadmePanel)]
+pub fn readme_panel(props: &ReadmePanelProps) -> Html {
+    // Errors are kept as the result so the panel can say so instead of loading forever
+    let readme = use_repo_detail(props.repo.clone(), true, |key| async move {
+        Ok(readme::fetch_readme(&key).await)
+    });
+
+    let note = |text: &'static str| {
+        html! {
+            <p class="readme-note">
+                {text}{" "}
+                <a href={props.html_url.clone()} target="_blank" rel="noopener noreferrer">
+                    {"View on GitHub ↗"}
+                </a>
+            </p>
+        }
+    };
+
+    html! {
+        <section class="readme-panel" aria-label="README">
+            {match readme {
+                None => html! { <p class="readme-note">{"Loading README…"}</p> },
+                Some(Ok(Some(readme))) => html! { <Markdown document={readme.document} /> },
+                Some(Ok(None)) => note("This repo has no README."),
+                Some(Err(_)) => note("The README could not be loaded right now."),
+            }}
+        </section>
+    }
+}
@@ -1,3 +1,8 @@
+use crate::api::{commit_activity, languages, releases, screenshots};
+use crate::components::language_bar::theme_color;
+use crate::components::{LanguageBar, ReadmePanel, ScreenshotPlaceholder, Sparkline, TimeAgo};
+use crate::hooks::{use_detail_budget, use_in_view, use_repo_detail};
+use crate::models::release::compact;
 use crate::models::Repo;
 use yew::prelude::*;
 
@@ -15,63 +20,238 @@ pub struct RepoCardProps {
 
 #[function_component(RepoCard)]
 pub fn repo_card(props: &RepoCardProps) -> Html {
-    let lang_color = match props.repo.language.as_deref() {
-        Some("Rust") => "var(--accent-cyan)",
-        Some("C++") => "var(--accent-aqua)",
-        Some("Python") => "var(--accent-green)",
-        Some("Liquid") => "var(--accent-light)",
-        _ => "var(--text-muted)",
+    let lang_color = props
+        .repo
+        .language
+        .as_deref()
+        .and_then(theme_color)
+        .unwrap_or("var(--text-muted)");
+
+    let repo = &props.repo;
+    let key = repo.key();
+
+    // Per-repo lookups wait until the card is near the viewport, to spare the rate limit
+    let node = use_node_ref();
+    let in_view = use_in_view(node.clone());
+    let on_github = key.provider() == "github";
+    let languages = use_repo_detail(key.clone(), in_view && on_github, |key| async move {
+        languages::fetch_languages(&key).await
+    });
+    let language_bar = languages.map(|languages| html! { <LanguageBar {languages} /> });
+
+    // The costlier extras only for the first few cards seen, or once the visitor reaches for
+    // one; the others say so rather than silently showing less
+    let engaged = use_state_eq(|| false);
+    let engage = {
+        let engaged = engaged.clone();
+        Callback::from(move |()| engaged.set(true))
+    };
+    let readme_open = use_state_eq(|| false);
+    let details = use_detail_budget(in_view && on_github, *engaged || *readme_open) && on_github;
+    // May poll for a while as GitHub computes the statistics; the card renders meanwhile
+    let activity = use_repo_detail(key.clone(), details, |key| async move {
+        commit_activity::fetch_commit_activity(&key).await
+    });
+    let sparkline = activity.map(|activity| html! { <Sparkline {activity} /> });
+
+    // A hand-picked screenshot wins; otherwise one is looked up, with a generated placeholder
+    // until it arrives, while it waits its turn, when the repo has no image, or when the
+    // lookup or the image fails
+    let manual = screenshots::manual_screenshot(&key);
+    let discovered = use_repo_detail(key.clone(), details && manual.is_none(), |key| async move {
+        screenshots::fetch_screenshot(&key).await
+    })
+    .flatten();
+    let deferred = in_view && on_github && !details;
+    let broken = use_state_eq(|| false);
+    let preview = match discovered {
+        Some(src) if !*broken => {
+            let onerror = {
+                let broken = broken.clone();
+                Callback::from(move |_: Event| broken.set(true))
+            };
+            html! {
+                <img
+                    {src}
+                    alt={repo.name.clone()}
+                    class="repo-screenshot"
+                    loading="lazy"
+                    referrerpolicy="no-referrer"
+                    {onerror}
+                />
+            }
+        }
+        _ => html! {
+            <ScreenshotPlaceholder
+                repo={key.clone()}
+                name={repo.name.clone()}
+                language={repo.language.clone().map(AttrValue::from)}
+            />
+        },
     };
 
-    let screenshot_block = props.repo.screenshot.as_ref().map(|img| {
-        let wrap_class = if props.show_poem {
-            "repo-screenshot-wrap poem-visible"
-        } else {
-            "repo-screenshot-wrap"
-        };
+    let toggle_readme = {
+        let readme_open = readme_open.clone();
+        Callback::from(move |_: MouseEvent| readme_open.set(!*readme_open))
+    };
+
+    let slug = repo.slug().to_string();
+    let release = use_repo_detail(key.clone(), details, move |key| {
+        let slug = slug.clone();
+        async move { releases::fetch_release(&key, &slug).await }
+    })
+    .flatten();
+    let release_badge = release.map(|release| {
         html! {
-            <div class={wrap_class}>
-                if props.show_poem {
-                    <div class="poem-over-screenshot" aria-hidden="true">
-                        <div class="poem-over-screenshot-content">
-                            { for POEM.split("\n\n").map(|stanza| html! {
-                                <p class="poem-stanza">{stanza}</p>
-                            }) }
-                        </div>
-                    </div>
+            <a
+                href={release.html_url.clone()}
+                target="_blank"
+                rel="noopener noreferrer"
+                class="repo-chip repo-release"
+                title={release.summary()}
+            >
+                {&release.tag}
+                if release.downloads > 0 {
+                    <span class="repo-release-downloads">
+                        {"⬇ "}{compact(release.downloads)}
+                    </span>
                 }
-                <img src={img.clone()} alt={props.repo.name.clone()} class="repo-screenshot-full" />
-            </div>
+            </a>
         }
     });
 
-    html! {
-        <a
-            href={props.repo.html_url.clone()}
-            target="_blank"
-            rel="noopener noreferrer"
-            class="repo-card"
-        >
-            if let Some(block) = screenshot_block {
-                {block}
+    // `pushed_at` tracks code changes; `updated_at` also moves on stars and renames
+    let last_change = repo.pushed_at.or(repo.updated_at);
+
+    let meta = html! {
+        <div class="repo-meta">
+            {for sparkline}
+            if let Some(at) = last_change {
+                <TimeAgo {at} label="Updated" />
+            }
+            if repo.stargazers_count > 0 {
+                <span title="Stars">{"★ "}{repo.stargazers_count}</span>
+            }
+            if repo.forks_count > 0 {
+                <span title="Forks">{"⑂ "}{repo.forks_count}</span>
+            }
+            if repo.open_issues_count > 0 {
+                <span title="Open issues and pull requests">{"◎ "}{repo.open_issues_count}</span>
+            }
+            if let Some(ref license) = repo.license {
+                <span class="repo-chip repo-license" title={license.name.clone()}>
+                    {license.label()}
+                </span>
+            }
+        </div>
+    };
+
+    // Links and controls sit beside the card link, not inside it: interactive content cannot nest
+    let actions = html! {
+        <div class="repo-actions">
+            {for release_badge}
+            if let Some(url) = repo.demo_url() {
+                <a href={url.to_string()} target="_blank" rel="noopener noreferrer" class="repo-demo">
+                    {"Live demo ↗"}
+                </a>
+            }
+            if on_github {
+                <button
+                    type="button"
+                    class="repo-readme-toggle"
+                    aria-expanded={readme_open.to_string()}
+                    onclick={toggle_readme}
+                >
+                    {"README"}
+                </button>
+            }
+            if deferred {
+                <span class="repo-deferred" title="Loaded on demand to spare GitHub's rate limit">
+                    {"Hover to load details"}
+                </span>
+            }
+        </div>
+    };
+
+    let badges = html! {
+        <>
+            if repo.archived {
+                <span class="repo-badge repo-badge-archived">{"Archived"}</span>
+            }
+            if repo.fork {
+                <span class="repo-badge">{"Fork"}</span>
+            }
+        </>
+    };
+
+    let screenshot = match manual {
+        Some(img) => {
+            let wrap_class = if props.show_poem {
+                "repo-screenshot-wrap poem-visible"
             } else {
-                <div class="repo-card-header">
-                    <h3 class="repo-name">{&props.repo.name}</h3>
-                    if let Some(ref lang) = props.repo.language {
-                        <span class="repo-lang" style={format!("--lang-color: {}", lang_color)}>
-                            {lang}
-                        </span>
+                "repo-screenshot-wrap"
+            };
+            html! {
+                <div class={wrap_class}>
+                    if props.show_poem {
+                        <div class="poem-over-screenshot" aria-hidden="true">
+                            <div class="poem-over-screenshot-content">
+                                { for POEM.split("\n\n").map(|stanza| html! {
+                                    <p class="poem-stanza">{stanza}</p>
+                                }) }
+                            </div>
+                        </div>
                     }
+                    <img src={img} alt={props.repo.name.clone()} class="repo-screenshot-full" />
                 </div>
-                if let Some(ref desc) = props.repo.description {
-                    <p class="repo-desc">{desc}</p>
-                }
-                <div class="repo-meta">
-                    if props.repo.stargazers_count > 0 {
-                        <span>{"★ "}{props.repo.stargazers_count}</span>
+            }
+        }
+        None => preview,
+    };
+
+    html! {
+        <>
+            <div
+                class="repo-card"
+                ref={node}
+                onmouseenter={engage.reform(|_: MouseEvent| ())}
+                onfocusin={engage.reform(|_: FocusEvent| ())}
+            >
+                <a
+                    href={props.repo.html_url.clone()}
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    class="repo-card-link"
+                >
+                    {screenshot}
+                    <div class="repo-card-header">
+                        <h3 class="repo-name">{&repo.name}</h3>
+                        {badges}
+                        if let Some(ref lang) = repo.language {
+                            <span class="repo-lang" style={format!("--lang-color: {}", lang_color)}>
+                                {lang}
+                            </span>
+                        }
+                    </div>
+                    if let Some(ref desc) = repo.description {
+                        <p class="repo-desc">{desc}</p>
                     }
-                </div>
+                    if !repo.topics.is_empty() {
+                        <ul class="repo-topics">
+                            { for repo.topics.iter().map(|topic| html! {
+                                <li class="repo-chip">{topic}</li>
+                            }) }
+                        </ul>
+                    }
+                    {for language_bar}
+                    {meta}
+                </a>
+                {actions}
+            </div>
+            // Outside the card: README links cannot nest inside the card link
+            if *readme_open {
+                <ReadmePanel repo={key.clone()} html_url={repo.html_url.clone()} />
             }
-        </a>
+        </>
     }
 }
@@ -15,7 +15,7 @@ pub fn repo_grid(props: &RepoGridProps) -> Html {
             {props.repos.iter().enumerate().map(|(i, repo)| {
                 let show_poem = i == 0 && props.show_poem;
                 html! {
-                    <div key={repo.name.clone()} class="scroll-reveal">
+                    <div key={repo.key().to_string()} class="scroll-reveal">
                         <RepoCard repo={repo.clone()} show_poem={show_poem} />
                     </div>
                 }
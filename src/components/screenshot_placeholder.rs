@@ -0,0 +1,87 @@
+use crate::components::language_bar::{fnv1a, language_color};
+use crate::models::RepoKey;
+use yew::prelude::*;
+
+/// Bubbles drawn over the gradient, inside a 320 × 160 viewBox.
+const BUBBLES: u32 = 9;
+
+#[derive(Properties, PartialEq)]
+pub struct ScreenshotPlaceholderProps {
+    pub repo: RepoKey,
+    pub name: AttrValue,
+    #[prop_or_default]
+    pub language: Option<AttrValue>,
+}
+
+/// Stand-in card image for repos without a screenshot: the repo's initials over a deep-water
+/// gradient tinted by its language, with bubbles placed from a hash of the name so each repo
+/// keeps the same picture across renders.
+#[function_component(ScreenshotPlaceholder)]
+pub fn screenshot_placeholder(props: &ScreenshotPlaceholderProps) -> Html {
+    let hash = fnv1a(&props.name);
+    let tint = props
+        .language
+        .as_deref()
+        .map(language_color)
+        .unwrap_or_else(|| "var(--accent-soft)".to_string());
+    // Several cards share the page, so gradient ids must not collide: the name alone would,
+    // for same-named repos of different owners
+    let gradient = format!("placeholder-{:08x}", fnv1a(props.repo.as_str()));
+
+    let bubbles = (0..BUBBLES).map(|i| {
+        let h = hash.rotate_left(i * 7).wrapping_mul(0x9e37_79b9 ^ i);
+        html! {
+            <circle
+                cx={(20 + h % 280).to_string()}
+                cy={(16 + (h >> 9) % 128).to_string()}
+                r={(2 + (h >> 17) % 9).to_string()}
+                class="repo-placeholder-bubble"
+            />
+        }
+    });
+
+    html! {
+        <svg
+            class="repo-screenshot repo-placeholder"
+            viewBox="0 0 320 160"
+            preserveAspectRatio="xMidYMid slice"
+            role="img"
+            aria-label={format!("{} (no screenshot)", props.name)}
+        >
+            <defs>
+                <linearGradient id={gradient.clone()} x1="0" y1="0" x2="0.4" y2="1">
+                    <stop offset="0" style={format!("stop-color: {}; stop-opacity: 0.55", tint)} />
+                    <stop offset="1" style="stop-color: var(--bg-ocean)" />
+                </linearGradient>
+            </defs>
+            <rect width="320" height="160" fill={format!("url(#{})", gradient)} />
+            { for bubbles }
+            <text x="160" y="92" text-anchor="middle" class="repo-placeholder-initials">
+                {initials(&props.name)}
+            </text>
+            if let Some(ref language) = props.language {
+                <text x="160" y="128" text-anchor="middle" class="repo-placeholder-language">
+                    {language}
+                </text>
+            }
+        </svg>
+    }
+}
+
+/// Up to two letters from the words of a repo name (`Game-of-Ur` → `GO`, `Senet` → `SE`).
+fn initials(name: &str) -> String {
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let letters: String = match words.as_slice() {
+        [] => "?".to_string(),
+        [word] => word.chars().take(2).collect(),
+        [first, second, ..] => first
+            .chars()
+            .take(1)
+            .chain(second.chars().take(1))
+            .collect(),
+    };
+    letters.to_uppercase()
+}
@@ -0,0 +1,88 @@
+use crate::models::CommitActivity;
+use yew::prelude::*;
+
+/// Drawing size in SVG user units; the stylesheet scales it.
+const WIDTH: f64 = 104.0;
+const HEIGHT: f64 = 24.0;
+/// Room for the stroke and the end marker at the edges.
+const PAD: f64 = 2.0;
+
+#[derive(Properties, PartialEq)]
+pub struct SparklineProps {
+    pub activity: CommitActivity,
+}
+
+/// Commits per week over the last year as a small line with a filled area beneath it; each
+/// week has a hover target with its date and count.
+#[function_component(Sparkline)]
+pub fn sparkline(props: &SparklineProps) -> Html {
+    let activity = &props.activity;
+    let weeks = &activity.weekly;
+    if weeks.len() < 2 || activity.is_quiet() {
+        return Html::default();
+    }
+
+    let max = f64::from(weeks.iter().copied().max().unwrap_or(1).max(1));
+    let step = (WIDTH - 2.0 * PAD) / (weeks.len() - 1) as f64;
+    let points: Vec<(f64, f64)> = weeks
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let x = PAD + i as f64 * step;
+            let y = HEIGHT - PAD - f64::from(count) / max * (HEIGHT - 2.0 * PAD);
+            (x, y)
+        })
+        .collect();
+    let line: String = points
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (first_x, _) = points[0];
+    let (last_x, last_y) = points[points.len() - 1];
+    let area = format!(
+        "M{:.1},{:.1} L{} L{:.1},{:.1} Z",
+        first_x,
+        HEIGHT - PAD,
+        line.replace(' ', " L"),
+        last_x,
+        HEIGHT - PAD
+    );
+
+    let targets = points.iter().enumerate().map(|(i, (x, _))| {
+        let commits = match weeks[i] {
+            1 => "1 commit".to_string(),
+            n => format!("{} commits", n),
+        };
+        let label = match activity.week_start(i) {
+            Some(start) => format!("Week of {}: {}", &start.to_iso()[..10], commits),
+            None => commits,
+        };
+        html! {
+            <rect
+                x={format!("{:.1}", x - step / 2.0)}
+                y="0"
+                width={format!("{:.1}", step)}
+                height={HEIGHT.to_string()}
+                class="sparkline-target"
+            >
+                <title>{label}</title>
+            </rect>
+        }
+    });
+
+    html! {
+        <svg
+            class="sparkline"
+            viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)}
+            preserveAspectRatio="none"
+            role="img"
+            aria-label={format!("{} commits in the last {} weeks", activity.total(), weeks.len())}
+        >
+            <path d={area} class="sparkline-area" />
+            <polyline points={line} class="sparkline-line" />
+            <circle cx={format!("{:.1}", last_x)} cy={format!("{:.1}", last_y)} r="1.8" class="sparkline-end" />
+            { for targets }
+        </svg>
+    }
+}
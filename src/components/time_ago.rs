@@ -0,0 +1,55 @@
+use crate::api::clock::{Clock, SystemClock};
+use crate::models::timestamp::{RelativeTime, Timestamp};
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TimeAgoProps {
+    pub at: Timestamp,
+    /// Leading text, e.g. "Updated"
+    #[prop_or_default]
+    pub label: AttrValue,
+}
+
+/// "Updated 3 days ago" in the reader's locale, with the full date and time as a tooltip.
+#[function_component(TimeAgo)]
+pub fn time_ago(props: &TimeAgoProps) -> Html {
+    let now = Timestamp::from_unix_ms(SystemClock.now_ms() as i64);
+    let relative = format_relative(props.at.relative_to(now));
+    let text = if props.label.is_empty() {
+        relative
+    } else {
+        format!("{} {}", props.label, relative)
+    };
+
+    html! {
+        <time class="time-ago" datetime={props.at.to_iso()} title={format_absolute(props.at)}>
+            {text}
+        </time>
+    }
+}
+
+/// `Intl.RelativeTimeFormat` ("yesterday", "vor 3 Tagen"), or English where it is missing.
+fn format_relative(relative: RelativeTime) -> String {
+    let supported = js_sys::Reflect::get(&js_sys::global(), &"Intl".into())
+        .and_then(|intl| js_sys::Reflect::get(&intl, &"RelativeTimeFormat".into()))
+        .is_ok_and(|ctor| ctor.is_function());
+    if !supported {
+        return relative.to_english();
+    }
+    let options = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&options, &"numeric".into(), &"auto".into());
+    js_sys::Intl::RelativeTimeFormat::new(&js_sys::Array::new(), &options)
+        .format(relative.value as f64, relative.unit.as_str())
+        .into()
+}
+
+/// Long date and short time in the reader's locale and time zone.
+fn format_absolute(at: Timestamp) -> String {
+    let options = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&options, &"dateStyle".into(), &"long".into());
+    let _ = js_sys::Reflect::set(&options, &"timeStyle".into(), &"short".into());
+    js_sys::Date::new(&JsValue::from_f64(at.unix_ms() as f64))
+        .to_locale_string("default", &options)
+        .into()
+}
@@ -0,0 +1,62 @@
+// Lazy-loading trigger: flips to true once an element comes near the viewport
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+use yew::prelude::*;
+
+/// How far outside the viewport an element counts as "in view", so data is ready on arrival.
+const ROOT_MARGIN: &str = "200px";
+
+/// True from the first time `node` is within [`ROOT_MARGIN`] of the viewport; never resets.
+///
+/// Browsers without `IntersectionObserver` report true right away.
+#[hook]
+pub fn use_in_view(node: NodeRef) -> bool {
+    let seen = use_state_eq(|| false);
+    {
+        let seen = seen.clone();
+        use_effect_with(node, move |node| {
+            let mut observer = None;
+            let mut callback = None;
+            if let Some(element) = node.cast::<Element>() {
+                let on_change = {
+                    let seen = seen.clone();
+                    Closure::<dyn FnMut(Array, IntersectionObserver)>::new(
+                        move |entries: Array, observer: IntersectionObserver| {
+                            let visible = entries.iter().any(|entry| {
+                                entry
+                                    .unchecked_into::<IntersectionObserverEntry>()
+                                    .is_intersecting()
+                            });
+                            if visible {
+                                seen.set(true);
+                                observer.disconnect();
+                            }
+                        },
+                    )
+                };
+                let init = IntersectionObserverInit::new();
+                init.set_root_margin(ROOT_MARGIN);
+                match IntersectionObserver::new_with_options(
+                    on_change.as_ref().unchecked_ref(),
+                    &init,
+                ) {
+                    Ok(o) => {
+                        o.observe(&element);
+                        observer = Some(o);
+                    }
+                    Err(_) => seen.set(true),
+                }
+                callback = Some(on_change);
+            }
+            move || {
+                if let Some(observer) = observer {
+                    observer.disconnect();
+                }
+                drop(callback);
+            }
+        });
+    }
+    *seen
+}
@@ -0,0 +1,7 @@
+mod in_view;
+mod refresh_scheduler;
+mod repo_detail;
+
+pub use in_view::use_in_view;
+pub use refresh_scheduler::{use_refresh_scheduler, RefreshConfig};
+pub use repo_detail::{use_detail_budget, use_repo_detail};
@@ -0,0 +1,93 @@
+// Refresh scheduler: mount fetch, periodic refresh while visible, catch-up when the tab returns
+use gloo_events::EventListener;
+use gloo_timers::callback::Interval;
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{window, VisibilityState};
+use yew::prelude::*;
+
+/// Scheduling knobs for [`use_refresh_scheduler`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefreshConfig {
+    /// Time between periodic refreshes while the tab is visible
+    pub interval_ms: u32,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 30 * 60 * 1000, // 30 min
+        }
+    }
+}
+
+struct SchedulerState {
+    last_run_ms: f64,
+    /// `None` while the tab is hidden; dropping it cancels the timer
+    interval: Option<Interval>,
+}
+
+/// Calls `on_refresh` on mount, then every `config.interval_ms` while the tab is visible.
+///
+/// Timers live as long as the component. Hiding the tab pauses them; showing it again runs
+/// a refresh right away if one came due while hidden.
+#[hook]
+pub fn use_refresh_scheduler(config: RefreshConfig, on_refresh: Callback<()>) {
+    use_effect_with(config, move |config| {
+        let interval_ms = config.interval_ms;
+        let state = Rc::new(RefCell::new(SchedulerState {
+            last_run_ms: 0.0,
+            interval: None,
+        }));
+
+        let run: Rc<dyn Fn()> = Rc::new({
+            let state = state.clone();
+            move || {
+                state.borrow_mut().last_run_ms = js_sys::Date::now();
+                on_refresh.emit(());
+            }
+        });
+
+        let start = {
+            let state = state.clone();
+            let run = run.clone();
+            move || {
+                let run = run.clone();
+                state.borrow_mut().interval = Some(Interval::new(interval_ms, move || run()));
+            }
+        };
+
+        run();
+
+        let cleanup_state = state.clone();
+        let document = window().and_then(|w| w.document());
+        // Opened in a background tab: `visibilitychange` starts the timer once it is shown
+        if document
+            .as_ref()
+            .is_none_or(|d| d.visibility_state() == VisibilityState::Visible)
+        {
+            start();
+        }
+        let listener = document.map(|document| {
+            let doc = document.clone();
+            EventListener::new(&document, "visibilitychange", move |_| {
+                if doc.visibility_state() == VisibilityState::Visible {
+                    let overdue =
+                        js_sys::Date::now() - state.borrow().last_run_ms >= f64::from(interval_ms);
+                    if overdue {
+                        run();
+                    }
+                    start();
+                } else {
+                    state.borrow_mut().interval = None;
+                }
+            })
+        });
+
+        move || {
+            drop(listener);
+            // The interval's closure holds `state`; break the cycle explicitly
+            cleanup_state.borrow_mut().interval = None;
+        }
+    });
+}
@@ -0,0 +1,73 @@
+// Per-repo detail loading for cards: waits until asked, then fetches once per repo
+use crate::api::FetchError;
+use crate::models::RepoKey;
+use std::cell::Cell;
+use std::future::Future;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Loads one detail of `repo` (languages, …) once `enabled` turns true, e.g. when its card
+/// scrolls into view. `None` until it arrives, and for good if it cannot be fetched.
+#[hook]
+pub fn use_repo_detail<T, F, Fut>(repo: RepoKey, enabled: bool, fetch: F) -> Option<T>
+where
+    T: Clone + PartialEq + 'static,
+    F: Fn(RepoKey) -> Fut + 'static,
+    Fut: Future<Output = Result<T, FetchError>> + 'static,
+{
+    let detail = use_state_eq(|| None::<T>);
+    {
+        let detail = detail.clone();
+        use_effect_with((repo, enabled), move |(repo, enabled)| {
+            // Results landing after unmount or a repo switch are dropped (they are cached anyway)
+            let current = Rc::new(Cell::new(true));
+            detail.set(None);
+            if *enabled {
+                let current = current.clone();
+                let pending = fetch(repo.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    // Errors (quota held back, network) just leave the detail out
+                    if let Ok(value) = pending.await {
+                        if current.get() {
+                            detail.set(Some(value));
+                        }
+                    }
+                });
+            }
+            move || current.set(false)
+        });
+    }
+    (*detail).clone()
+}
+
+/// Cards per page load that fetch optional details (screenshots, releases, activity) as soon as
+/// they are seen. Each costs a few requests of the 60/h unauthenticated quota, so the rest
+/// wait until the visitor shows interest.
+const EAGER_CARDS: usize = 4;
+
+thread_local! {
+    static EAGER_LEFT: Cell<usize> = const { Cell::new(EAGER_CARDS) };
+}
+
+/// Whether a card should load its optional details: once it is among the first
+/// [`EAGER_CARDS`] to come `in_view`, or once `engaged` (hovered, focused, expanded).
+#[hook]
+pub fn use_detail_budget(in_view: bool, engaged: bool) -> bool {
+    let eager = use_state_eq(|| false);
+    {
+        let eager = eager.clone();
+        use_effect_with(in_view, move |in_view| {
+            if *in_view && !*eager {
+                let granted = EAGER_LEFT.with(|left| {
+                    let granted = left.get() > 0;
+                    left.set(left.get().saturating_sub(1));
+                    granted
+                });
+                if granted {
+                    eager.set(true);
+                }
+            }
+        });
+    }
+    *eager || engaged
+}
@@ -1,6 +1,8 @@
 pub mod api;
 pub mod app;
 pub mod components;
+pub mod hooks;
+pub mod markdown;
 pub mod models;
 pub mod scene;
 
@@ -0,0 +1,95 @@
+// HTML character references: the named ones READMEs actually use, plus numeric forms
+
+/// Named references worth decoding; anything else is left as typed.
+const NAMED: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("lt", "<"),
+    ("gt", ">"),
+    ("quot", "\""),
+    ("apos", "'"),
+    ("nbsp", "\u{a0}"),
+    ("copy", "©"),
+    ("reg", "®"),
+    ("trade", "™"),
+    ("hellip", "…"),
+    ("mdash", "—"),
+    ("ndash", "–"),
+    ("middot", "·"),
+    ("bull", "•"),
+    ("rarr", "→"),
+    ("larr", "←"),
+    ("harr", "↔"),
+    ("uarr", "↑"),
+    ("darr", "↓"),
+    ("times", "×"),
+    ("deg", "°"),
+    ("laquo", "«"),
+    ("raquo", "»"),
+    ("lsquo", "‘"),
+    ("rsquo", "’"),
+    ("ldquo", "“"),
+    ("rdquo", "”"),
+    ("check", "✓"),
+];
+
+/// Decodes a reference at the start of `s` (which begins with `&`), returning the text and
+/// how many bytes it used.
+fn decode_at(s: &str) -> Option<(String, usize)> {
+    // Names run at most 32 bytes, so the `;` is not searched for past them
+    let end = s.get(1..)?.bytes().take(33).position(|b| b == b';')? + 1;
+    let body = &s[1..end];
+    if body.is_empty() {
+        return None;
+    }
+    let text = if let Some(num) = body.strip_prefix('#') {
+        let code = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => num.parse().ok()?,
+        };
+        // NUL and invalid code points become the replacement character, as in browsers
+        let c = char::from_u32(code)
+            .filter(|c| *c != '\0')
+            .unwrap_or('\u{fffd}');
+        c.to_string()
+    } else {
+        NAMED
+            .iter()
+            .find(|(name, _)| *name == body)
+            .map(|(_, text)| text.to_string())?
+    };
+    Some((text, end + 1))
+}
+
+/// Decodes every recognised reference in `s`.
+pub fn decode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        match decode_at(rest) {
+            Some((text, used)) => {
+                out.push_str(&text);
+                rest = &rest[used..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_and_numeric_references() {
+        assert_eq!(decode("a &amp; b &lt;c&gt;"), "a & b <c>");
+        assert_eq!(decode("&#169; &#x2192; &#0;"), "© → \u{fffd}");
+        assert_eq!(decode("AT&T &unknown; &"), "AT&T &unknown; &");
+    }
+}
@@ -0,0 +1,222 @@
+// Raw HTML in READMEs, reduced to the few tags worth keeping: images, line breaks, links
+// and the paragraphs and headings that center a logo. Everything else is dropped.
+use super::entities::decode;
+
+/// A tag, classified by what the document makes of it.
+#[derive(Debug, PartialEq)]
+pub enum Tag {
+    Image {
+        src: String,
+        alt: String,
+        title: Option<String>,
+    },
+    Break,
+    Link(String),
+    EndLink,
+    /// `<script>` or `<style>`, whose contents are not text
+    Hidden,
+    EndHidden,
+    /// `<p>`, `<div>` or `<h1>`–`<h6>`, opening or closing; `Some(level)` opens a heading
+    Block {
+        heading: Option<u8>,
+    },
+    Other,
+}
+
+/// A run of text, with whitespace collapsed as a browser would, or a tag.
+#[derive(Debug, PartialEq)]
+pub enum Node {
+    Text(String),
+    Tag(Tag),
+}
+
+/// Classifies a single tag, e.g. one inline HTML event.
+pub fn tag(html: &str) -> Tag {
+    let Some(inner) = html.strip_prefix('<') else {
+        return Tag::Other;
+    };
+    let inner = inner.strip_suffix('>').unwrap_or(inner);
+    let inner = inner.strip_suffix('/').unwrap_or(inner);
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let name_len = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let name = inner[..name_len].to_ascii_lowercase();
+    let attrs = &inner[name_len..];
+    match (name.as_str(), closing) {
+        ("img", false) => Tag::Image {
+            src: attr(attrs, "src").unwrap_or_default(),
+            alt: attr(attrs, "alt").unwrap_or_default(),
+            title: attr(attrs, "title"),
+        },
+        ("br", _) => Tag::Break,
+        ("a", false) => Tag::Link(attr(attrs, "href").unwrap_or_default()),
+        ("a", true) => Tag::EndLink,
+        ("script" | "style", false) => Tag::Hidden,
+        ("script" | "style", true) => Tag::EndHidden,
+        ("p" | "div", _) => Tag::Block { heading: None },
+        ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", _) => Tag::Block {
+            heading: (!closing).then(|| name.as_bytes()[1] - b'0'),
+        },
+        _ => Tag::Other,
+    }
+}
+
+/// Splits an HTML block into text and tags, dropping comments.
+pub fn nodes(html: &str) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        text.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+        let len = if rest.starts_with("<!--") {
+            // An unclosed comment hides the rest, as in a browser
+            rest.find("-->").map_or(rest.len(), |end| end + 3)
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') {
+            match tag_len(rest) {
+                Some(len) => {
+                    push_text(&mut nodes, &mut text);
+                    nodes.push(Node::Tag(tag(&rest[..len])));
+                    len
+                }
+                // An unterminated tag leaves the rest as text
+                None => break,
+            }
+        } else {
+            text.push('<');
+            1
+        };
+        rest = &rest[len..];
+    }
+    text.push_str(rest);
+    push_text(&mut nodes, &mut text);
+    nodes
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &mut String) {
+    if !text.is_empty() {
+        nodes.push(Node::Text(collapse(&decode(text))));
+        text.clear();
+    }
+}
+
+/// Turns each run of whitespace into one space.
+fn collapse(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut space = false;
+    for c in s.chars() {
+        if c.is_ascii_whitespace() {
+            space = true;
+            continue;
+        }
+        if space {
+            out.push(' ');
+            space = false;
+        }
+        out.push(c);
+    }
+    if space {
+        out.push(' ');
+    }
+    out
+}
+
+/// Length of the tag at the start of `s`, up to its unquoted `>`.
+fn tag_len(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, b) in s.bytes().enumerate().skip(1) {
+        match (quote, b) {
+            (Some(q), _) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Value of the attribute `name` in `attrs`, with character references decoded.
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_len = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        rest = rest[key_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, len) = match after.as_bytes().first() {
+                    Some(&q @ (b'"' | b'\'')) => match after[1..].find(q as char) {
+                        Some(end) => (&after[1..end + 1], end + 2),
+                        None => (&after[1..], after.len()),
+                    },
+                    _ => {
+                        let end = after
+                            .find(|c: char| c.is_ascii_whitespace())
+                            .unwrap_or(after.len());
+                        (&after[..end], end)
+                    }
+                };
+                rest = &after[len..];
+                value
+            }
+            None => "",
+        };
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_classified() {
+        assert_eq!(
+            tag(r#"<IMG alt='X &amp; Y' src="x.png"/>"#),
+            Tag::Image {
+                src: "x.png".to_string(),
+                alt: "X & Y".to_string(),
+                title: None,
+            }
+        );
+        assert_eq!(tag("<br/>"), Tag::Break);
+        assert_eq!(
+            tag("<a href=https://x.io>"),
+            Tag::Link("https://x.io".to_string())
+        );
+        assert_eq!(tag("<h2 align=center>"), Tag::Block { heading: Some(2) });
+        assert_eq!(tag("</h2>"), Tag::Block { heading: None });
+        assert_eq!(tag("<span onclick=\"x\">"), Tag::Other);
+        assert_eq!(tag("<!-- note -->"), Tag::Other);
+    }
+
+    #[test]
+    fn blocks_split_into_text_and_tags() {
+        assert_eq!(
+            nodes("<p>\n  a <!-- b --> c\n</p><a href=\"x>y\">z</a> <3"),
+            [
+                Node::Tag(Tag::Block { heading: None }),
+                Node::Text(" a c ".to_string()),
+                Node::Tag(Tag::Block { heading: None }),
+                Node::Tag(Tag::Link("x>y".to_string())),
+                Node::Text("z".to_string()),
+                Node::Tag(Tag::EndLink),
+                Node::Text(" <3".to_string()),
+            ]
+        );
+    }
+}
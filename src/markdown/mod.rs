@@ -0,0 +1,255 @@
+// GitHub-flavoured Markdown for READMEs. Sources are parsed into a plain AST that
+// components render node by node, so nothing from a README reaches the DOM as raw markup.
+mod entities;
+mod html;
+mod parse;
+
+use serde::{Deserialize, Serialize};
+
+pub use parse::parse;
+
+/// Quotes, lists, emphasis and links nest at most this deep; anything deeper is flattened
+/// into its parent, so a hostile README cannot exhaust the stack while rendering.
+const MAX_NESTING: usize = 32;
+
+/// A parsed Markdown document.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Block {
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    Code {
+        /// First word of the fence's info string
+        lang: Option<String>,
+        text: String,
+    },
+    Quote(Vec<Block>),
+    List {
+        /// Start number for ordered lists, `None` for bullets
+        start: Option<u64>,
+        /// Tight lists render item paragraphs without `<p>` spacing
+        tight: bool,
+        items: Vec<ListItem>,
+    },
+    Table {
+        align: Vec<Align>,
+        head: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    Rule,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ListItem {
+    /// `Some` for GFM task list items (`- [ ]`, `- [x]`)
+    pub checked: Option<bool>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Align {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Inline {
+    Text(String),
+    Code(String),
+    Emphasis(Vec<Inline>),
+    Strong(Vec<Inline>),
+    Strikethrough(Vec<Inline>),
+    /// An empty `href` (dropped as unsafe) renders as its content only
+    Link {
+        href: String,
+        title: Option<String>,
+        content: Vec<Inline>,
+    },
+    /// An empty `src` renders as the alt text
+    Image {
+        src: String,
+        alt: String,
+        title: Option<String>,
+    },
+    LineBreak,
+}
+
+/// What a URL in the document points at, for [`Document::rewrite_urls`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UrlKind {
+    Link,
+    Image,
+}
+
+impl Document {
+    /// Rewrites every link and image URL in place; `rewrite` returning `None` drops it.
+    pub fn rewrite_urls(&mut self, rewrite: &mut impl FnMut(UrlKind, &str) -> Option<String>) {
+        for block in &mut self.blocks {
+            block.rewrite_urls(rewrite);
+        }
+    }
+
+    /// Image sources in document order, including images inside links.
+    pub fn images(&self) -> Vec<&str> {
+        let mut srcs = Vec::new();
+        for block in &self.blocks {
+            block.collect_images(&mut srcs);
+        }
+        srcs
+    }
+}
+
+impl Block {
+    fn rewrite_urls(&mut self, rewrite: &mut impl FnMut(UrlKind, &str) -> Option<String>) {
+        match self {
+            Block::Heading { content, .. } | Block::Paragraph(content) => {
+                rewrite_inlines(content, rewrite)
+            }
+            Block::Quote(blocks) => blocks.iter_mut().for_each(|b| b.rewrite_urls(rewrite)),
+            Block::List { items, .. } => items
+                .iter_mut()
+                .flat_map(|item| item.blocks.iter_mut())
+                .for_each(|b| b.rewrite_urls(rewrite)),
+            Block::Table { head, rows, .. } => head
+                .iter_mut()
+                .chain(rows.iter_mut().flatten())
+                .for_each(|cell| rewrite_inlines(cell, rewrite)),
+            Block::Code { .. } | Block::Rule => {}
+        }
+    }
+
+    fn collect_images<'a>(&'a self, srcs: &mut Vec<&'a str>) {
+        match self {
+            Block::Heading { content, .. } | Block::Paragraph(content) => {
+                collect_images(content, srcs)
+            }
+            Block::Quote(blocks) => blocks.iter().for_each(|b| b.collect_images(srcs)),
+            Block::List { items, .. } => items
+                .iter()
+                .flat_map(|item| item.blocks.iter())
+                .for_each(|b| b.collect_images(srcs)),
+            Block::Table { head, rows, .. } => head
+                .iter()
+                .chain(rows.iter().flatten())
+                .for_each(|cell| collect_images(cell, srcs)),
+            Block::Code { .. } | Block::Rule => {}
+        }
+    }
+}
+
+fn rewrite_inlines(
+    inlines: &mut [Inline],
+    rewrite: &mut impl FnMut(UrlKind, &str) -> Option<String>,
+) {
+    for inline in inlines {
+        match inline {
+            Inline::Link { href, content, .. } => {
+                *href = rewrite(UrlKind::Link, href).unwrap_or_default();
+                rewrite_inlines(content, rewrite);
+            }
+            Inline::Image { src, .. } => {
+                *src = rewrite(UrlKind::Image, src).unwrap_or_default();
+            }
+            Inline::Emphasis(content)
+            | Inline::Strong(content)
+            | Inline::Strikethrough(content) => rewrite_inlines(content, rewrite),
+            Inline::Text(_) | Inline::Code(_) | Inline::LineBreak => {}
+        }
+    }
+}
+
+fn collect_images<'a>(inlines: &'a [Inline], srcs: &mut Vec<&'a str>) {
+    for inline in inlines {
+        match inline {
+            Inline::Image { src, .. } => srcs.push(src),
+            Inline::Link { content, .. }
+            | Inline::Emphasis(content)
+            | Inline::Strong(content)
+            | Inline::Strikethrough(content) => collect_images(content, srcs),
+            Inline::Text(_) | Inline::Code(_) | Inline::LineBreak => {}
+        }
+    }
+}
+
+/// Concatenated text of `inlines`, e.g. for image alt text.
+pub fn plain_text(inlines: &[Inline]) -> String {
+    let mut out = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) | Inline::Code(text) => out.push_str(text),
+            Inline::Emphasis(content)
+            | Inline::Strong(content)
+            | Inline::Strikethrough(content)
+            | Inline::Link { content, .. } => out.push_str(&plain_text(content)),
+            Inline::Image { alt, .. } => out.push_str(alt),
+            Inline::LineBreak => out.push(' '),
+        }
+    }
+    out
+}
+
+/// True for URLs that are safe to put in `href` / `src`: http(s), mailto, and anything
+/// without a scheme (relative paths and fragments).
+pub fn is_safe_url(url: &str) -> bool {
+    match url.split_once(':') {
+        // A colon after `/`, `?` or `#` is part of a relative path, not a scheme
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => {
+            matches!(
+                scheme.to_ascii_lowercase().as_str(),
+                "http" | "https" | "mailto"
+            )
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsafe_schemes_are_rejected() {
+        assert!(is_safe_url("https://example.com"));
+        assert!(is_safe_url("docs/setup.md"));
+        assert!(is_safe_url("#usage"));
+        assert!(is_safe_url("img/a:b.png"));
+        assert!(is_safe_url("MAILTO:me@example.com"));
+        assert!(!is_safe_url("javascript:alert(1)"));
+        assert!(!is_safe_url("JavaScript:alert(1)"));
+        assert!(!is_safe_url("data:text/html,<script>"));
+        assert!(!is_safe_url("vbscript:x"));
+    }
+
+    #[test]
+    fn rewrite_reaches_nested_links_and_images() {
+        let mut doc = parse("> - [![badge](b.svg)](ci.yml)\n\n| a |\n|---|\n| [x](y) |");
+        let mut seen = Vec::new();
+        doc.rewrite_urls(&mut |kind, url| {
+            seen.push((kind, url.to_string()));
+            Some(format!("/{}", url))
+        });
+        assert_eq!(
+            seen,
+            [
+                (UrlKind::Link, "ci.yml".to_string()),
+                (UrlKind::Image, "b.svg".to_string()),
+                (UrlKind::Link, "y".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn images_are_listed_in_order() {
+        let doc = parse("[![ci](ci.svg)](x)\n\n- ![shot](a.png)\n\n| ![b](b.png) |\n|---|");
+        assert_eq!(doc.images(), ["ci.svg", "a.png", "b.png"]);
+    }
+}
@@ -0,0 +1,948 @@
+// Builds the AST from pulldown-cmark's event stream. Containers live on explicit stacks
+// rather than the call stack, and nesting past `MAX_NESTING` is flattened into the
+// innermost kept container.
+use super::html::{self, Node, Tag as HtmlTag};
+use super::{is_safe_url, plain_text, Align, Block, Document, Inline, ListItem, MAX_NESTING};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+
+/// Parses GitHub-flavoured Markdown: tables, strikethrough and task lists on top of
+/// CommonMark. Links and images with unsafe URLs keep their text but lose the URL.
+pub fn parse(text: &str) -> Document {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut builder = Builder::new();
+    for event in Parser::new_ext(text, options) {
+        builder.event(event);
+    }
+    builder.finish()
+}
+
+/// A block container still being filled.
+enum Container {
+    Root(Vec<Block>),
+    Quote(Vec<Block>),
+    List {
+        start: Option<u64>,
+        tight: bool,
+        items: Vec<ListItem>,
+    },
+    Item(ListItem),
+    Table {
+        align: Vec<Align>,
+        head: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+        row: Vec<Vec<Inline>>,
+    },
+}
+
+/// The leaf block whose content is being collected.
+enum Leaf {
+    /// `implicit` for the bare text of a tight list item, or text loose in an HTML block
+    Paragraph {
+        implicit: bool,
+    },
+    Heading(u8),
+    Cell,
+    Code {
+        lang: Option<String>,
+        text: String,
+    },
+    Html(String),
+}
+
+/// An inline container still being filled.
+enum Span {
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Link {
+        href: String,
+        title: Option<String>,
+    },
+    /// Opened by an `<a>` tag, which need not be closed
+    HtmlLink(String),
+    Image {
+        src: String,
+        title: Option<String>,
+    },
+    /// A link inside a link, kept as its content
+    Plain,
+}
+
+struct Builder {
+    containers: Vec<Container>,
+    /// Container starts past the nesting limit whose ends are still to come
+    dropped_containers: usize,
+    leaf: Option<Leaf>,
+    /// The leaf's content; `spans[i]` is filled into `inlines[i + 1]`
+    inlines: Vec<Vec<Inline>>,
+    spans: Vec<Span>,
+    /// Span starts past the nesting limit whose ends are still to come
+    dropped_spans: usize,
+    /// Open `<script>` and `<style>` tags
+    hidden: usize,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            containers: vec![Container::Root(Vec::new())],
+            dropped_containers: 0,
+            leaf: None,
+            inlines: Vec::new(),
+            spans: Vec::new(),
+            dropped_spans: 0,
+            hidden: 0,
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if let Some(Leaf::Code {
+                    text: ref mut code, ..
+                }) = self.leaf
+                {
+                    code.push_str(&text);
+                } else {
+                    self.text(&text);
+                }
+            }
+            Event::Code(code) => self.inline(Inline::Code(code.into_string())),
+            Event::Html(html) => {
+                if let Some(Leaf::Html(ref mut block)) = self.leaf {
+                    block.push_str(&html);
+                }
+            }
+            Event::InlineHtml(tag) => self.html_tag(html::tag(&tag)),
+            Event::SoftBreak => self.text("\n"),
+            Event::HardBreak => self.inline(Inline::LineBreak),
+            Event::Rule => self.block(Block::Rule),
+            Event::TaskListMarker(checked) => {
+                if let Some(Container::Item(item)) = self.containers.last_mut() {
+                    item.checked = Some(checked);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => {
+                self.close_implicit();
+                // Loose list items wrap their text in paragraphs; tight ones do not
+                if let [.., Container::List { tight, .. }, Container::Item(_)] =
+                    self.containers.as_mut_slice()
+                {
+                    *tight = false;
+                }
+                self.open_leaf(Leaf::Paragraph { implicit: false });
+            }
+            Tag::Heading { level, .. } => {
+                self.close_implicit();
+                self.open_leaf(Leaf::Heading(level as u8));
+            }
+            Tag::CodeBlock(kind) => {
+                self.close_implicit();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(str::to_string)
+                    }
+                    CodeBlockKind::Indented => None,
+                };
+                self.leaf = Some(Leaf::Code {
+                    lang,
+                    text: String::new(),
+                });
+            }
+            Tag::HtmlBlock => {
+                self.close_implicit();
+                self.leaf = Some(Leaf::Html(String::new()));
+            }
+            Tag::BlockQuote(_) => self.open_container(Container::Quote(Vec::new())),
+            Tag::List(start) => self.open_container(Container::List {
+                start,
+                tight: true,
+                items: Vec::new(),
+            }),
+            Tag::Item => {
+                // Items of a dropped list are dropped with it
+                if self.dropped_containers == 0
+                    && matches!(self.containers.last(), Some(Container::List { .. }))
+                {
+                    self.containers.push(Container::Item(ListItem {
+                        checked: None,
+                        blocks: Vec::new(),
+                    }));
+                } else {
+                    self.dropped_containers += 1;
+                }
+            }
+            Tag::Table(align) => {
+                self.close_implicit();
+                let align = align
+                    .into_iter()
+                    .map(|a| match a {
+                        Alignment::None => Align::None,
+                        Alignment::Left => Align::Left,
+                        Alignment::Center => Align::Center,
+                        Alignment::Right => Align::Right,
+                    })
+                    .collect();
+                self.containers.push(Container::Table {
+                    align,
+                    head: Vec::new(),
+                    rows: Vec::new(),
+                    row: Vec::new(),
+                });
+            }
+            Tag::TableCell => self.open_leaf(Leaf::Cell),
+            Tag::Emphasis => self.open_span(Span::Emphasis),
+            Tag::Strong => self.open_span(Span::Strong),
+            Tag::Strikethrough => self.open_span(Span::Strikethrough),
+            Tag::Link { .. } if self.in_link() => self.open_span(Span::Plain),
+            Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                ..
+            } => {
+                let href = match link_type {
+                    LinkType::Email => format!("mailto:{}", dest_url),
+                    _ => dest_url.into_string(),
+                };
+                self.open_span(Span::Link {
+                    href: safe(href),
+                    title: non_empty(title.into_string()),
+                });
+            }
+            Tag::Image {
+                dest_url, title, ..
+            } => self.open_span(Span::Image {
+                src: safe(dest_url.into_string()),
+                title: non_empty(title.into_string()),
+            }),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock | TagEnd::HtmlBlock => {
+                self.close_leaf()
+            }
+            TagEnd::TableCell => {
+                let cell = self.take_inlines();
+                self.leaf = None;
+                if let Some(Container::Table { row, .. }) = self.containers.last_mut() {
+                    row.push(cell);
+                }
+            }
+            TagEnd::TableHead => {
+                if let Some(Container::Table { head, row, .. }) = self.containers.last_mut() {
+                    *head = std::mem::take(row);
+                }
+            }
+            TagEnd::TableRow => {
+                if let Some(Container::Table {
+                    align, rows, row, ..
+                }) = self.containers.last_mut()
+                {
+                    let mut row = std::mem::take(row);
+                    // Rows take the header's width, padded or cut
+                    row.resize_with(align.len(), Vec::new);
+                    rows.push(row);
+                }
+            }
+            // Tables are never dropped, so their end is always their own
+            TagEnd::Table => {
+                if let Some(Container::Table {
+                    align, head, rows, ..
+                }) = self.containers.pop()
+                {
+                    self.block(Block::Table { align, head, rows });
+                }
+            }
+            TagEnd::BlockQuote(_) | TagEnd::List(_) | TagEnd::Item => {
+                self.close_implicit();
+                if self.dropped_containers > 0 {
+                    self.dropped_containers -= 1;
+                    return;
+                }
+                match self.containers.pop() {
+                    Some(Container::Quote(blocks)) => self.block(Block::Quote(blocks)),
+                    Some(Container::List {
+                        start,
+                        tight,
+                        items,
+                    }) => self.block(Block::List {
+                        start,
+                        tight,
+                        items,
+                    }),
+                    Some(Container::Item(item)) => {
+                        if let Some(Container::List { items, .. }) = self.containers.last_mut() {
+                            items.push(item);
+                        }
+                    }
+                    // Unbalanced events: keep the root and any table rather than lose them
+                    Some(other @ (Container::Root(_) | Container::Table { .. })) => {
+                        self.containers.push(other)
+                    }
+                    None => {}
+                }
+            }
+            TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Link
+            | TagEnd::Image => self.close_span(),
+            _ => {}
+        }
+    }
+
+    /// Opens a quote or list, or drops it past the nesting limit.
+    fn open_container(&mut self, container: Container) {
+        self.close_implicit();
+        let depth = self
+            .containers
+            .iter()
+            .filter(|c| matches!(c, Container::Quote(_) | Container::List { .. }))
+            .count();
+        if self.dropped_containers > 0 || depth >= MAX_NESTING {
+            self.dropped_containers += 1;
+        } else {
+            self.containers.push(container);
+        }
+    }
+
+    fn open_leaf(&mut self, leaf: Leaf) {
+        self.leaf = Some(leaf);
+        self.inlines = vec![Vec::new()];
+        self.spans.clear();
+        self.dropped_spans = 0;
+        self.hidden = 0;
+    }
+
+    /// Ends the bare text of a tight list item before the next block starts.
+    fn close_implicit(&mut self) {
+        if let Some(Leaf::Paragraph { implicit: true }) = self.leaf {
+            self.close_leaf();
+        }
+    }
+
+    fn close_leaf(&mut self) {
+        match self.leaf.take() {
+            Some(Leaf::Paragraph { .. }) => {
+                let content = self.take_inlines();
+                if !content.is_empty() {
+                    self.block(Block::Paragraph(content));
+                }
+            }
+            Some(Leaf::Heading(level)) => {
+                let content = self.take_inlines();
+                self.block(Block::Heading { level, content });
+            }
+            Some(Leaf::Code { lang, mut text }) => {
+                if text.ends_with('\n') {
+                    text.pop();
+                }
+                self.block(Block::Code { lang, text });
+            }
+            Some(Leaf::Html(html)) => self.html_block(&html),
+            Some(Leaf::Cell) | None => {}
+        }
+    }
+
+    /// The leaf's content, with spans left open (unclosed `<a>` tags) unwrapped.
+    fn take_inlines(&mut self) -> Vec<Inline> {
+        while !self.spans.is_empty() {
+            self.unwrap_span();
+        }
+        self.inlines.pop().unwrap_or_default()
+    }
+
+    /// Appends `block` to the innermost container.
+    fn block(&mut self, block: Block) {
+        match self.containers.last_mut() {
+            Some(Container::Root(blocks) | Container::Quote(blocks)) => blocks.push(block),
+            Some(Container::Item(item)) => item.blocks.push(block),
+            // Pulldown-cmark puts blocks in items, and nothing in tables but cells
+            Some(Container::List { .. } | Container::Table { .. }) | None => {}
+        }
+    }
+
+    fn open_span(&mut self, span: Span) {
+        self.ensure_leaf();
+        if self.dropped_spans > 0 || self.spans.len() >= MAX_NESTING {
+            self.dropped_spans += 1;
+            return;
+        }
+        self.spans.push(span);
+        self.inlines.push(Vec::new());
+    }
+
+    fn close_span(&mut self) {
+        if self.dropped_spans > 0 {
+            self.dropped_spans -= 1;
+            return;
+        }
+        // `<a>` tags opened inside the span and never closed end with it
+        while let Some(Span::HtmlLink(_)) = self.spans.last() {
+            self.unwrap_span();
+        }
+        let (Some(span), Some(content)) = (self.spans.pop(), self.inlines.pop()) else {
+            return;
+        };
+        let inline = match span {
+            Span::Emphasis => Inline::Emphasis(content),
+            Span::Strong => Inline::Strong(content),
+            Span::Strikethrough => Inline::Strikethrough(content),
+            Span::Link { href, title } => Inline::Link {
+                href,
+                title,
+                content,
+            },
+            Span::HtmlLink(href) => Inline::Link {
+                href,
+                title: None,
+                content,
+            },
+            Span::Image { src, title } => Inline::Image {
+                src,
+                alt: plain_text(&content),
+                title,
+            },
+            Span::Plain => {
+                content.into_iter().for_each(|inline| self.inline(inline));
+                return;
+            }
+        };
+        self.inline(inline);
+    }
+
+    /// Drops the innermost span, keeping its content.
+    fn unwrap_span(&mut self) {
+        self.spans.pop();
+        if let Some(content) = self.inlines.pop() {
+            for inline in content {
+                self.inline(inline);
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.hidden == 0 {
+            self.inline(Inline::Text(text.to_string()));
+        }
+    }
+
+    fn inline(&mut self, inline: Inline) {
+        if let Inline::Text(ref text) = inline {
+            if let Some(Inline::Text(last)) = self.current().last_mut() {
+                last.push_str(text);
+                return;
+            }
+        }
+        self.current().push(inline);
+    }
+
+    /// The innermost inline container.
+    fn current(&mut self) -> &mut Vec<Inline> {
+        self.ensure_leaf();
+        self.inlines.last_mut().expect("leaf has content")
+    }
+
+    /// Starts a paragraph for bare text outside any leaf.
+    fn ensure_leaf(&mut self) {
+        if self.leaf.is_none() {
+            self.open_leaf(Leaf::Paragraph { implicit: true });
+        }
+    }
+
+    fn in_link(&self) -> bool {
+        self.spans
+            .iter()
+            .any(|s| matches!(s, Span::Link { .. } | Span::HtmlLink(_)))
+    }
+
+    fn html_tag(&mut self, tag: HtmlTag) {
+        match tag {
+            HtmlTag::Image { src, alt, title } => self.inline(Inline::Image {
+                src: safe(src),
+                alt,
+                title,
+            }),
+            HtmlTag::Break => self.inline(Inline::LineBreak),
+            // Links do not nest
+            HtmlTag::Link(href) if !self.in_link() && self.spans.len() < MAX_NESTING => {
+                self.ensure_leaf();
+                self.spans.push(Span::HtmlLink(safe(href)));
+                self.inlines.push(Vec::new());
+            }
+            HtmlTag::EndLink if matches!(self.spans.last(), Some(Span::HtmlLink(_))) => {
+                self.close_html_link()
+            }
+            HtmlTag::Hidden => self.hidden += 1,
+            HtmlTag::EndHidden => self.hidden = self.hidden.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Closes the innermost span, opened by an `<a>` tag, as a link.
+    fn close_html_link(&mut self) {
+        if let (Some(Span::HtmlLink(href)), Some(content)) = (self.spans.pop(), self.inlines.pop())
+        {
+            self.inline(Inline::Link {
+                href,
+                title: None,
+                content,
+            });
+        }
+    }
+
+    /// Keeps the text, images and links of an HTML block, as paragraphs and headings.
+    fn html_block(&mut self, html: &str) {
+        for node in html::nodes(html) {
+            match node {
+                Node::Text(text) => {
+                    let blank = text.trim().is_empty();
+                    if self.leaf.is_some() || !blank {
+                        self.text(&text);
+                    }
+                }
+                Node::Tag(HtmlTag::Block { heading }) => {
+                    self.close_html_leaf();
+                    if let Some(level) = heading {
+                        self.open_leaf(Leaf::Heading(level));
+                    }
+                }
+                Node::Tag(tag) => self.html_tag(tag),
+            }
+        }
+        self.close_html_leaf();
+    }
+
+    /// Closes a leaf started inside an HTML block, trimming the whitespace at its ends.
+    fn close_html_leaf(&mut self) {
+        let Some(leaf) = self.leaf.take() else {
+            return;
+        };
+        let mut content = self.take_inlines();
+        if let Some(Inline::Text(first)) = content.first_mut() {
+            *first = first.trim_start().to_string();
+        }
+        if let Some(Inline::Text(last)) = content.last_mut() {
+            *last = last.trim_end().to_string();
+        }
+        content.retain(|inline| !matches!(inline, Inline::Text(text) if text.is_empty()));
+        if content.is_empty() {
+            return;
+        }
+        match leaf {
+            Leaf::Heading(level) => self.block(Block::Heading { level, content }),
+            _ => self.block(Block::Paragraph(content)),
+        }
+    }
+
+    fn finish(mut self) -> Document {
+        self.close_leaf();
+        // Pulldown-cmark closes every container it opens, so only the root is left
+        let blocks = match self.containers.into_iter().next() {
+            Some(Container::Root(blocks)) => blocks,
+            _ => Vec::new(),
+        };
+        Document { blocks }
+    }
+}
+
+/// `url`, or an empty string if it must not reach the page.
+fn safe(url: String) -> String {
+    if is_safe_url(&url) {
+        url
+    } else {
+        String::new()
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    (!s.is_empty()).then_some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    fn para(s: &str) -> Block {
+        Block::Paragraph(vec![text(s)])
+    }
+
+    fn link(href: &str, content: Vec<Inline>) -> Inline {
+        Inline::Link {
+            href: href.to_string(),
+            title: None,
+            content,
+        }
+    }
+
+    /// The inlines of a document that is a single paragraph.
+    fn inlines(s: &str) -> Vec<Inline> {
+        match parse(s).blocks.as_slice() {
+            [Block::Paragraph(content)] => content.clone(),
+            other => panic!("expected one paragraph, got {:?}", other),
+        }
+    }
+
+    fn block_depth(blocks: &[Block]) -> usize {
+        blocks
+            .iter()
+            .map(|block| match block {
+                Block::Quote(inner) => 1 + block_depth(inner),
+                Block::List { items, .. } => {
+                    1 + items
+                        .iter()
+                        .map(|i| block_depth(&i.blocks))
+                        .max()
+                        .unwrap_or(0)
+                }
+                Block::Paragraph(content) => inline_depth(content),
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn inline_depth(inlines: &[Inline]) -> usize {
+        inlines
+            .iter()
+            .map(|inline| match inline {
+                Inline::Emphasis(content)
+                | Inline::Strong(content)
+                | Inline::Strikethrough(content)
+                | Inline::Link { content, .. } => 1 + inline_depth(content),
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn headings_paragraphs_and_rules() {
+        let doc = parse("# Senet #\n\nAn old\ngame.\n\nRules\n-----\n\n***\n####### seven");
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::Heading {
+                    level: 1,
+                    content: vec![text("Senet")],
+                },
+                para("An old\ngame."),
+                Block::Heading {
+                    level: 2,
+                    content: vec![text("Rules")],
+                },
+                Block::Rule,
+                para("####### seven"),
+            ]
+        );
+    }
+
+    #[test]
+    fn fenced_and_indented_code() {
+        let doc = parse(
+            "```rust title=main.rs\nfn main() {}\n\n  // done\n```\n\n    $ cargo run\n\n    ok\n",
+        );
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::Code {
+                    lang: Some("rust".to_string()),
+                    text: "fn main() {}\n\n  // done".to_string(),
+                },
+                Block::Code {
+                    lang: None,
+                    text: "$ cargo run\n\nok".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_lists_and_task_items() {
+        let doc = parse(
+            "- [x] done\n- [ ] todo\n  1. first\n  2. second\n- plain\n\n3) three\n\n4) four",
+        );
+        let item = |checked, blocks| ListItem { checked, blocks };
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::List {
+                    start: None,
+                    tight: true,
+                    items: vec![
+                        item(Some(true), vec![para("done")]),
+                        item(
+                            Some(false),
+                            vec![
+                                para("todo"),
+                                Block::List {
+                                    start: Some(1),
+                                    tight: true,
+                                    items: vec![
+                                        item(None, vec![para("first")]),
+                                        item(None, vec![para("second")]),
+                                    ],
+                                },
+                            ]
+                        ),
+                        item(None, vec![para("plain")]),
+                    ],
+                },
+                Block::List {
+                    start: Some(3),
+                    tight: false,
+                    items: vec![
+                        item(None, vec![para("three")]),
+                        item(None, vec![para("four")]),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn quotes_nest() {
+        let doc = parse("> quoted\nlazy\n> > inner\n\nafter");
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::Quote(vec![
+                    para("quoted\nlazy"),
+                    Block::Quote(vec![para("inner")]),
+                ]),
+                para("after"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_with_alignment() {
+        let doc = parse("| Key | Action |  |\n|:---|:---:|--:|\n| `W` | Up \\| forward |\n| S | Down | x | extra |\n\nafter");
+        let cell = |s: &str| vec![text(s)];
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::Table {
+                    align: vec![Align::Left, Align::Center, Align::Right],
+                    head: vec![cell("Key"), cell("Action"), vec![]],
+                    rows: vec![
+                        vec![
+                            vec![Inline::Code("W".to_string())],
+                            cell("Up | forward"),
+                            vec![],
+                        ],
+                        vec![cell("S"), cell("Down"), cell("x")],
+                    ],
+                },
+                para("after"),
+            ]
+        );
+    }
+
+    #[test]
+    fn emphasis_links_and_code() {
+        assert_eq!(
+            inlines("*a **b** c* ~~old~~ `x*y` [a](b/c.md \"T\")"),
+            [
+                Inline::Emphasis(vec![
+                    text("a "),
+                    Inline::Strong(vec![text("b")]),
+                    text(" c")
+                ]),
+                text(" "),
+                Inline::Strikethrough(vec![text("old")]),
+                text(" "),
+                Inline::Code("x*y".to_string()),
+                text(" "),
+                Inline::Link {
+                    href: "b/c.md".to_string(),
+                    title: Some("T".to_string()),
+                    content: vec![text("a")],
+                },
+            ]
+        );
+        assert_eq!(inlines("snake_case_name"), [text("snake_case_name")]);
+    }
+
+    #[test]
+    fn reference_links_and_autolinks() {
+        assert_eq!(
+            inlines("See [the docs] <https://a.io> <me@x.org>\n\n[The Docs]: https://docs.rs/x \"Docs\""),
+            [
+                text("See "),
+                Inline::Link {
+                    href: "https://docs.rs/x".to_string(),
+                    title: Some("Docs".to_string()),
+                    content: vec![text("the docs")],
+                },
+                text(" "),
+                link("https://a.io", vec![text("https://a.io")]),
+                text(" "),
+                link("mailto:me@x.org", vec![text("me@x.org")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn badge_is_an_image_inside_a_link() {
+        assert_eq!(
+            inlines("[![CI *x*](https://ci/badge.svg)](https://ci)"),
+            [link(
+                "https://ci",
+                vec![Inline::Image {
+                    src: "https://ci/badge.svg".to_string(),
+                    alt: "CI x".to_string(),
+                    title: None,
+                }]
+            )]
+        );
+    }
+
+    #[test]
+    fn unsafe_urls_keep_only_their_text() {
+        assert_eq!(
+            inlines("[a](javascript:alert(1)) ![b](data:image/png,x) <a href=\"vbscript:x\">c</a>"),
+            [
+                link("", vec![text("a")]),
+                text(" "),
+                Inline::Image {
+                    src: String::new(),
+                    alt: "b".to_string(),
+                    title: None,
+                },
+                text(" "),
+                link("", vec![text("c")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_html_is_reduced_to_safe_nodes() {
+        assert_eq!(
+            inlines(
+                r#"a<br/>b <img src="x.png" alt="X &amp; Y"> <script>alert(1)</script><span onclick="x">c</span><!-- note -->"#
+            ),
+            [
+                text("a"),
+                Inline::LineBreak,
+                text("b "),
+                Inline::Image {
+                    src: "x.png".to_string(),
+                    alt: "X & Y".to_string(),
+                    title: None,
+                },
+                text(" c"),
+            ]
+        );
+        assert_eq!(
+            inlines(r#"<a href="https://x.io"><b>X</b></a> *<a href="y">z*"#),
+            [
+                link("https://x.io", vec![text("X")]),
+                text(" "),
+                Inline::Emphasis(vec![text("z")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn html_blocks_keep_headings_and_images() {
+        let doc = parse("<!-- hidden -->\n\n<h1 align=\"center\">\n  <img src=\"logo.png\" alt=\"Logo\"> Senet\n</h1>\n\n<div>\n</div>\n\n<p align=\"center\">A <b>board</b> game</p>");
+        assert_eq!(
+            doc.blocks,
+            [
+                Block::Heading {
+                    level: 1,
+                    content: vec![
+                        Inline::Image {
+                            src: "logo.png".to_string(),
+                            alt: "Logo".to_string(),
+                            title: None,
+                        },
+                        text(" Senet"),
+                    ],
+                },
+                para("A board game"),
+            ]
+        );
+    }
+
+    #[test]
+    fn breaks_escapes_and_entities() {
+        assert_eq!(
+            inlines("a  \nb\\\nc\nd \\*e\\* &copy;"),
+            [
+                text("a"),
+                Inline::LineBreak,
+                text("b"),
+                Inline::LineBreak,
+                text("c\nd *e* ©"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deep_nesting_is_flattened() {
+        let quotes = parse(&format!("{}deep", "> ".repeat(50_000)));
+        assert_eq!(block_depth(&quotes.blocks), MAX_NESTING);
+        let lists = parse(&format!("{}deep", "- ".repeat(50_000)));
+        assert_eq!(block_depth(&lists.blocks), MAX_NESTING);
+        let emphasis = parse(&format!(
+            "{}b{}",
+            "*a ".repeat(50_000),
+            " c*".repeat(50_000)
+        ));
+        assert_eq!(block_depth(&emphasis.blocks), MAX_NESTING);
+        let links = parse(&format!("{}x{}", "[".repeat(50_000), "](y)".repeat(50_000)));
+        assert!(block_depth(&links.blocks) <= 1);
+        let html = parse(&"<a href=x>".repeat(50_000));
+        assert!(block_depth(&html.blocks) <= 1);
+    }
+
+    #[test]
+    fn long_hostile_input_parses_quickly() {
+        for unit in [
+            "*a _b ",
+            "~~a ",
+            "[a](",
+            "![",
+            "[a](b (",
+            "[a](<",
+            "<a href=x>",
+            "<a href=\"",
+            "<!-- ",
+            "&amp",
+            "`",
+            "| a ",
+            "> - 1. ",
+        ] {
+            let input = unit.repeat(200_000 / unit.len());
+            let started = Instant::now();
+            parse(&input);
+            // Linear work takes milliseconds here; a quadratic pass takes minutes
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "{:?} took {:?}",
+                unit,
+                started.elapsed()
+            );
+        }
+    }
+}
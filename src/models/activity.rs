@@ -0,0 +1,205 @@
+// Public activity per day, bucketed from the GitHub events feed for the contribution calendar
+use crate::models::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// The event types the calendar tooltips break down; the rest count as `Other`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventKind {
+    Push,
+    PullRequest,
+    Issue,
+    Release,
+    Other,
+}
+
+impl EventKind {
+    /// From the `type` field of an event, e.g. `PushEvent`.
+    pub fn from_type(event_type: &str) -> Self {
+        match event_type {
+            "PushEvent" => Self::Push,
+            "PullRequestEvent" | "PullRequestReviewEvent" | "PullRequestReviewCommentEvent" => {
+                Self::PullRequest
+            }
+            "IssuesEvent" | "IssueCommentEvent" => Self::Issue,
+            "ReleaseEvent" => Self::Release,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Event counts for one day.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DayActivity {
+    #[serde(default)]
+    pub pushes: u32,
+    #[serde(default)]
+    pub pull_requests: u32,
+    #[serde(default)]
+    pub issues: u32,
+    #[serde(default)]
+    pub releases: u32,
+    #[serde(default)]
+    pub other: u32,
+}
+
+impl DayActivity {
+    pub fn add(&mut self, kind: EventKind) {
+        let count = match kind {
+            EventKind::Push => &mut self.pushes,
+            EventKind::PullRequest => &mut self.pull_requests,
+            EventKind::Issue => &mut self.issues,
+            EventKind::Release => &mut self.releases,
+            EventKind::Other => &mut self.other,
+        };
+        *count += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.pushes + self.pull_requests + self.issues + self.releases + self.other
+    }
+
+    /// e.g. `3 pushes, 1 pull request, 2 other`; `No activity` for an empty day.
+    pub fn breakdown(&self) -> String {
+        let parts: Vec<String> = [
+            (self.pushes, "push", "pushes"),
+            (self.pull_requests, "pull request", "pull requests"),
+            (self.issues, "issue", "issues"),
+            (self.releases, "release", "releases"),
+            (self.other, "other", "other"),
+        ]
+        .into_iter()
+        .filter(|(count, ..)| *count > 0)
+        .map(|(count, one, many)| format!("{} {}", count, if count == 1 { one } else { many }))
+        .collect();
+        if parts.is_empty() {
+            "No activity".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Activity by day, keyed by days since the Unix epoch (UTC); days without events are left out.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Activity(BTreeMap<i64, DayActivity>);
+
+impl Activity {
+    /// Buckets `events` by UTC day, dropping those before `since_day`.
+    pub fn from_events(
+        events: impl IntoIterator<Item = (Timestamp, EventKind)>,
+        since_day: i64,
+    ) -> Self {
+        let mut days = BTreeMap::new();
+        for (at, kind) in events {
+            let day = day_number(at);
+            if day >= since_day {
+                days.entry(day)
+                    .or_insert_with(DayActivity::default)
+                    .add(kind);
+            }
+        }
+        Self(days)
+    }
+
+    pub fn day(&self, day: i64) -> DayActivity {
+        self.0.get(&day).copied().unwrap_or_default()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.0.values().map(DayActivity::total).sum()
+    }
+
+    /// Events on the busiest day, the top of the colour scale.
+    pub fn busiest(&self) -> u32 {
+        self.0.values().map(DayActivity::total).max().unwrap_or(0)
+    }
+}
+
+/// Days since the Unix epoch of the UTC day containing `at`.
+pub fn day_number(at: Timestamp) -> i64 {
+    at.unix_ms().div_euclid(MS_PER_DAY)
+}
+
+/// Midnight UTC at the start of `day`.
+pub fn day_start(day: i64) -> Timestamp {
+    Timestamp::from_unix_ms(day * MS_PER_DAY)
+}
+
+/// 0 for Sunday through 6 for Saturday (1970-01-01 was a Thursday).
+pub fn weekday(day: i64) -> usize {
+    (day + 4).rem_euclid(7) as usize
+}
+
+/// `weeks` columns of seven days, Sunday first, the last one holding `today`. Days after
+/// `today` in the last column are included; callers leave them blank.
+pub fn calendar(today: i64, weeks: usize) -> Vec<[i64; 7]> {
+    let first = today - weekday(today) as i64 - 7 * (weeks as i64 - 1);
+    (0..weeks as i64)
+        .map(|week| std::array::from_fn(|d| first + week * 7 + d as i64))
+        .collect()
+}
+
+/// Shade from 0 (nothing) to 4 (as busy as the busiest day).
+pub fn level(total: u32, busiest: u32) -> u8 {
+    if total == 0 || busiest == 0 {
+        return 0;
+    }
+    (4 * total).div_ceil(busiest).clamp(1, 4) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> Timestamp {
+        Timestamp::parse(s).unwrap()
+    }
+
+    #[test]
+    fn events_are_bucketed_by_utc_day() {
+        let since = day_number(at("2024-03-01"));
+        let activity = Activity::from_events(
+            [
+                (at("2024-03-02T23:59:59Z"), EventKind::Push),
+                (
+                    at("2024-03-02T00:00:00Z"),
+                    EventKind::from_type("PullRequestEvent"),
+                ),
+                (at("2024-03-03T01:00:00+02:00"), EventKind::Push),
+                (at("2024-02-29T12:00:00Z"), EventKind::Release),
+            ],
+            since,
+        );
+        let march_2 = activity.day(day_number(at("2024-03-02")));
+        assert_eq!((march_2.pushes, march_2.pull_requests), (2, 1));
+        assert_eq!(march_2.breakdown(), "2 pushes, 1 pull request");
+        assert_eq!(activity.total(), 3);
+        assert_eq!(activity.busiest(), 3);
+        assert_eq!(activity.day(since).breakdown(), "No activity");
+    }
+
+    #[test]
+    fn calendar_ends_with_the_current_week() {
+        // A Wednesday
+        let today = day_number(at("2024-03-06"));
+        let weeks = calendar(today, 53);
+        assert_eq!(weeks.len(), 53);
+        assert_eq!(weekday(weeks[0][0]), 0);
+        assert_eq!(day_start(weeks[0][0]), at("2023-03-05"));
+        assert_eq!(weeks[52][3], today);
+        assert_eq!(day_start(weeks[52][6]), at("2024-03-09"));
+    }
+
+    #[test]
+    fn levels_scale_to_the_busiest_day() {
+        assert_eq!(level(0, 8), 0);
+        assert_eq!(level(1, 8), 1);
+        assert_eq!(level(3, 8), 2);
+        assert_eq!(level(8, 8), 4);
+        assert_eq!(level(1, 1), 4);
+    }
+}
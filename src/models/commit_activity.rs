@@ -0,0 +1,48 @@
+// Weekly commit counts for one repo over the last year, as GitHub's statistics report them
+use crate::models::Timestamp;
+use serde::{Deserialize, Serialize};
+
+const MS_PER_WEEK: i64 = 7 * 86_400_000;
+
+/// Commits per week, oldest first, from `GET /repos/{owner}/{repo}/stats/commit_activity`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CommitActivity {
+    /// Start (Sunday, 00:00 UTC) of the first week; `None` for a repo without commits
+    pub first_week: Option<Timestamp>,
+    pub weekly: Vec<u32>,
+}
+
+impl CommitActivity {
+    pub fn total(&self) -> u32 {
+        self.weekly.iter().sum()
+    }
+
+    /// No commits in the whole period; nothing worth drawing.
+    pub fn is_quiet(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Start of week `index`, counting from the oldest.
+    pub fn week_start(&self, index: usize) -> Option<Timestamp> {
+        self.first_week
+            .map(|first| Timestamp::from_unix_ms(first.unix_ms() + index as i64 * MS_PER_WEEK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_follow_the_first_one() {
+        let activity = CommitActivity {
+            first_week: Timestamp::parse("2024-03-03"),
+            weekly: vec![0, 4, 1],
+        };
+        assert_eq!(activity.total(), 5);
+        assert!(!activity.is_quiet());
+        assert_eq!(activity.week_start(2), Timestamp::parse("2024-03-17"));
+        assert!(CommitActivity::default().is_quiet());
+        assert_eq!(CommitActivity::default().week_start(0), None);
+    }
+}
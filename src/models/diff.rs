@@ -0,0 +1,97 @@
+// Structural diff between two repo lists, used to skip no-op re-renders
+use crate::models::{Repo, RepoKey};
+use std::collections::HashMap;
+
+/// What changed between two repo lists, by [`Repo::key`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepoDiff {
+    pub added: Vec<RepoKey>,
+    pub removed: Vec<RepoKey>,
+    /// Present in both lists with different fields
+    pub changed: Vec<RepoKey>,
+    /// Same repos, different order
+    pub reordered: bool,
+}
+
+impl RepoDiff {
+    /// Compares `old` against `new`.
+    pub fn between(old: &[Repo], new: &[Repo]) -> Self {
+        let old_by_key: HashMap<RepoKey, &Repo> = old.iter().map(|r| (r.key(), r)).collect();
+        let new_by_key: HashMap<RepoKey, &Repo> = new.iter().map(|r| (r.key(), r)).collect();
+
+        let mut diff = Self::default();
+        for (key, repo) in new.iter().map(|r| (r.key(), r)) {
+            match old_by_key.get(&key) {
+                None => diff.added.push(key),
+                Some(prev) if *prev != repo => diff.changed.push(key),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .map(Repo::key)
+            .filter(|key| !new_by_key.contains_key(key))
+            .collect();
+        diff.reordered = diff.added.is_empty()
+            && diff.removed.is_empty()
+            && old.iter().zip(new).any(|(a, b)| a.key() != b.key());
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, stars: u32) -> Repo {
+        Repo {
+            name: name.to_string(),
+            full_name: format!("azuree0/{}", name),
+            html_url: format!("https://github.com/azuree0/{}", name),
+            stargazers_count: stars,
+            ..Repo::default()
+        }
+    }
+
+    #[test]
+    fn identical_lists_are_empty() {
+        let repos = [repo("a", 1), repo("b", 2)];
+        let diff = RepoDiff::between(&repos, &repos.clone());
+        assert_eq!(diff, RepoDiff::default());
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_by_key() {
+        let diff = RepoDiff::between(&[repo("a", 1), repo("b", 2)], &[repo("b", 2), repo("c", 3)]);
+        assert_eq!(diff.added, [repo("c", 3).key()]);
+        assert_eq!(diff.removed, [repo("a", 1).key()]);
+        assert!(diff.changed.is_empty());
+        assert!(!diff.reordered);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn changed_fields_are_reported() {
+        let diff = RepoDiff::between(&[repo("a", 1), repo("b", 2)], &[repo("a", 1), repo("b", 5)]);
+        assert_eq!(diff.changed, [repo("b", 2).key()]);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(!diff.reordered);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn pure_reorder_is_not_empty() {
+        let diff = RepoDiff::between(&[repo("a", 1), repo("b", 2)], &[repo("b", 2), repo("a", 1)]);
+        assert!(diff.reordered);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+        assert!(!diff.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+// Per-language byte counts for one repo, as GitHub's linguist reports them
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bytes of code per language, from `GET /repos/{owner}/{repo}/languages`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Languages(BTreeMap<String, u64>);
+
+/// One segment of a language bar.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageShare {
+    pub name: String,
+    pub bytes: u64,
+    /// Of the repo total, 0.0 – 1.0
+    pub fraction: f64,
+}
+
+/// Label for the segment that collects languages too small to show on their own.
+pub const OTHER: &str = "Other";
+
+impl Languages {
+    pub fn total(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    /// Bytes of `language`, 0 if the repo has none.
+    pub fn bytes(&self, language: &str) -> u64 {
+        self.0.get(language).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Shares largest first. Languages below `min_fraction` are folded into one [`OTHER`]
+    /// share at the end, so a bar never has slivers too thin to see or hover.
+    pub fn shares(&self, min_fraction: f64) -> Vec<LanguageShare> {
+        let total = self.total();
+        if total == 0 {
+            return Vec::new();
+        }
+        let mut entries: Vec<(&String, &u64)> = self.0.iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let mut shares = Vec::new();
+        let mut other = 0;
+        for (name, &bytes) in entries {
+            let fraction = bytes as f64 / total as f64;
+            if fraction < min_fraction {
+                other += bytes;
+            } else {
+                shares.push(LanguageShare {
+                    name: name.clone(),
+                    bytes,
+                    fraction,
+                });
+            }
+        }
+        if other > 0 {
+            shares.push(LanguageShare {
+                name: OTHER.to_string(),
+                bytes: other,
+                fraction: other as f64 / total as f64,
+            });
+        }
+        shares
+    }
+}
+
+/// One language summed over many repos.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageTotal {
+    pub name: String,
+    pub bytes: u64,
+    /// Repos containing any code in this language
+    pub repos: usize,
+}
+
+/// Sums bytes and counts repos per language, largest total first.
+pub fn aggregate<'a>(per_repo: impl IntoIterator<Item = &'a Languages>) -> Vec<LanguageTotal> {
+    let mut totals: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+    for languages in per_repo {
+        for (name, &bytes) in languages.0.iter().filter(|(_, &b)| b > 0) {
+            let total = totals.entry(name).or_default();
+            total.0 += bytes;
+            total.1 += 1;
+        }
+    }
+    let mut totals: Vec<LanguageTotal> = totals
+        .into_iter()
+        .map(|(name, (bytes, repos))| LanguageTotal {
+            name: name.to_string(),
+            bytes,
+            repos,
+        })
+        .collect();
+    totals.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    totals
+}
+
+impl FromIterator<(String, u64)> for Languages {
+    fn from_iter<I: IntoIterator<Item = (String, u64)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn languages(entries: &[(&str, u64)]) -> Languages {
+        entries.iter().map(|&(n, b)| (n.to_string(), b)).collect()
+    }
+
+    #[test]
+    fn shares_are_sorted_largest_first() {
+        let langs = languages(&[("GLSL", 200), ("Rust", 700), ("JavaScript", 100)]);
+        let names: Vec<_> = langs.shares(0.0).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["Rust", "GLSL", "JavaScript"]);
+        assert_eq!(langs.shares(0.0)[0].fraction, 0.7);
+    }
+
+    #[test]
+    fn small_languages_fold_into_other() {
+        let langs = languages(&[("Rust", 970), ("Shell", 20), ("Makefile", 10)]);
+        let shares = langs.shares(0.05);
+        assert_eq!(shares.len(), 2);
+        assert_eq!((shares[1].name.as_str(), shares[1].bytes), (OTHER, 30));
+    }
+
+    #[test]
+    fn empty_repo_has_no_shares() {
+        assert!(Languages::default().shares(0.0).is_empty());
+        assert!(languages(&[("Rust", 0)]).is_empty());
+    }
+
+    #[test]
+    fn aggregate_sums_bytes_and_counts_repos() {
+        let a = languages(&[("Rust", 700), ("GLSL", 100)]);
+        let b = languages(&[("Rust", 300), ("JavaScript", 500), ("Shell", 0)]);
+        let totals = aggregate([&a, &b]);
+        let rows: Vec<_> = totals
+            .iter()
+            .map(|t| (t.name.as_str(), t.bytes, t.repos))
+            .collect();
+        assert_eq!(
+            rows,
+            [("Rust", 1_000, 2), ("JavaScript", 500, 1), ("GLSL", 100, 1)]
+        );
+    }
+
+    #[test]
+    fn decodes_github_response() {
+        let langs: Languages = serde_json::from_str(r#"{"Rust":1200,"GLSL":300}"#).unwrap();
+        assert_eq!(langs.total(), 1_500);
+    }
+}
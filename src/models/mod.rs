@@ -1,2 +1,14 @@
+pub mod activity;
+pub mod commit_activity;
+pub mod diff;
+pub mod languages;
+pub mod release;
 pub mod repo;
-pub use repo::Repo;
+pub mod timestamp;
+pub use activity::Activity;
+pub use commit_activity::CommitActivity;
+pub use diff::RepoDiff;
+pub use languages::{LanguageShare, LanguageTotal, Languages};
+pub use release::Release;
+pub use repo::{License, Repo, RepoKey};
+pub use timestamp::Timestamp;
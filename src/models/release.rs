@@ -0,0 +1,105 @@
+// The newest published version of a repo: its latest release, or its highest version tag
+use crate::models::timestamp::{self, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// What a version badge shows and links to.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Release {
+    /// Git tag, e.g. `v1.2.0`
+    pub tag: String,
+    /// Release title, when it says more than the tag
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The release page, or the tag's page when there is no release
+    pub html_url: String,
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    pub published_at: Option<Timestamp>,
+    /// Files attached to the release
+    #[serde(default)]
+    pub assets: usize,
+    /// Downloads summed over all assets
+    #[serde(default)]
+    pub downloads: u64,
+    /// Found through `/tags`: no release page, date or assets
+    #[serde(default)]
+    pub tag_only: bool,
+}
+
+impl Release {
+    /// One-line description for the badge tooltip, e.g.
+    /// `Senet 1.2 · released 2024-03-01 · 3 assets · 1.2k downloads`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.name.clone().unwrap_or_else(|| self.tag.clone())];
+        if let Some(at) = self.published_at {
+            parts.push(format!("released {}", &at.to_iso()[..10]));
+        }
+        if self.tag_only {
+            parts.push("tag only, no release".to_string());
+        } else {
+            parts.push(plural(self.assets as u64, "asset"));
+            if self.assets > 0 {
+                parts.push(plural(self.downloads, "download"));
+            }
+        }
+        parts.join(" · ")
+    }
+}
+
+fn plural(count: u64, noun: &str) -> String {
+    let s = if count == 1 { "" } else { "s" };
+    format!("{} {}{}", compact(count), noun, s)
+}
+
+/// `950`, `1.2k`, `34k`, `1.5M`: short enough for a tooltip line.
+pub fn compact(count: u64) -> String {
+    let scaled = |value: u64, unit: u64, suffix: &str| {
+        let tenths = value * 10 / unit;
+        if tenths < 100 && !tenths.is_multiple_of(10) {
+            format!("{}.{}{}", tenths / 10, tenths % 10, suffix)
+        } else {
+            format!("{}{}", tenths / 10, suffix)
+        }
+    };
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => scaled(count, 1_000, "k"),
+        _ => scaled(count, 1_000_000, "M"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_are_compacted() {
+        assert_eq!(compact(950), "950");
+        assert_eq!(compact(1_000), "1k");
+        assert_eq!(compact(1_250), "1.2k");
+        assert_eq!(compact(34_900), "34k");
+        assert_eq!(compact(1_500_000), "1.5M");
+    }
+
+    #[test]
+    fn summary_skips_what_a_tag_cannot_have() {
+        let release = Release {
+            tag: "v1.2.0".to_string(),
+            name: Some("Senet 1.2".to_string()),
+            published_at: Timestamp::parse("2024-03-01T12:00:00Z"),
+            assets: 3,
+            downloads: 1_204,
+            ..Release::default()
+        };
+        assert_eq!(
+            release.summary(),
+            "Senet 1.2 · released 2024-03-01 · 3 assets · 1.2k downloads"
+        );
+
+        let tag = Release {
+            tag: "v0.1".to_string(),
+            tag_only: true,
+            ..Release::default()
+        };
+        assert_eq!(tag.summary(), "v0.1 · tag only, no release");
+    }
+}
@@ -1,15 +1,191 @@
+use crate::models::timestamp::{self, Timestamp};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Repo {
+    #[serde(default)]
+    pub id: u64,
     pub name: String,
+    /// `owner/name`
+    #[serde(default)]
+    pub full_name: String,
     pub description: Option<String>,
     pub html_url: String,
     pub language: Option<String>,
     #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
     pub stargazers_count: u32,
-    pub updated_at: String,
-    /// Optional screenshot path (static fallback only)
-    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none", default)]
-    pub screenshot: Option<String>,
+    #[serde(default)]
+    pub forks_count: u32,
+    /// Open issues plus open pull requests, as GitHub counts them
+    #[serde(default)]
+    pub open_issues_count: u32,
+    #[serde(default)]
+    pub license: Option<License>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub fork: bool,
+    /// Project website; GitHub sends `""` when it was set and then cleared
+    #[serde(default)]
+    pub homepage: Option<String>,
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    pub created_at: Option<Timestamp>,
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    pub updated_at: Option<Timestamp>,
+    /// Last push to any branch (`updated_at` also moves on stars and settings changes)
+    #[serde(default, deserialize_with = "timestamp::lenient")]
+    pub pushed_at: Option<Timestamp>,
+}
+
+impl Repo {
+    /// Canonical identity used for keying, merging, dedup and overrides.
+    ///
+    /// Built from `full_name` when present (API repos) and from `html_url` otherwise
+    /// (hand-written entries), so the same repo from either source gets the same key.
+    pub fn key(&self) -> RepoKey {
+        let (host, path) = split_url(&self.html_url);
+        let provider = host
+            .trim_start_matches("www.")
+            .split('.')
+            .next()
+            .filter(|p| !p.is_empty())
+            .unwrap_or("github");
+        RepoKey::new(provider, self.slug_from(path))
+    }
+
+    /// `owner/name` as the host spells it, for links shown to visitors; [`Repo::key`]
+    /// lowercases it.
+    pub fn slug(&self) -> &str {
+        self.slug_from(split_url(&self.html_url).1)
+    }
+
+    fn slug_from<'a>(&'a self, path: Option<&'a str>) -> &'a str {
+        if self.full_name.contains('/') {
+            &self.full_name
+        } else {
+            path.filter(|p| p.contains('/')).unwrap_or(&self.name)
+        }
+    }
+
+    /// Live demo link, if the repo has a non-empty homepage.
+    pub fn demo_url(&self) -> Option<&str> {
+        self.homepage
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    }
+}
+
+/// `provider:owner/name`, lowercased: GitHub treats owner and repo names case-insensitively.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct RepoKey(String);
+
+impl RepoKey {
+    pub fn new(provider: &str, slug: &str) -> Self {
+        Self(format!("{}:{}", provider, slug).to_lowercase())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Hosting service, e.g. `github`.
+    pub fn provider(&self) -> &str {
+        self.0.split_once(':').map_or("", |(provider, _)| provider)
+    }
+
+    /// `owner/name` (or just `name` when the owner is unknown).
+    pub fn slug(&self) -> &str {
+        self.0.split_once(':').map_or(&self.0, |(_, slug)| slug)
+    }
+}
+
+impl fmt::Display for RepoKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// `https://github.com/owner/name/…` into `("github.com", Some("owner/name"))`.
+fn split_url(url: &str) -> (&str, Option<&str>) {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let path = path.trim_end_matches('/');
+    // Keep `owner/name`, drop deeper segments such as `/tree/main`
+    let slug_end = path
+        .match_indices('/')
+        .nth(1)
+        .map_or(path.len(), |(i, _)| i);
+    let slug = &path[..slug_end];
+    (host, (!slug.is_empty()).then_some(slug))
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct License {
+    pub key: String,
+    pub name: String,
+    /// `NOASSERTION` when GitHub could not identify the license
+    #[serde(default)]
+    pub spdx_id: Option<String>,
+}
+
+impl License {
+    /// SPDX id when GitHub recognised the license, its name otherwise.
+    pub fn label(&self) -> &str {
+        match self.spdx_id.as_deref() {
+            Some(id) if !id.is_empty() && id != "NOASSERTION" => id,
+            _ => &self.name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_and_static_entries_share_a_key() {
+        let api = Repo {
+            name: "Senet".to_string(),
+            full_name: "azuree0/Senet".to_string(),
+            html_url: "https://github.com/azuree0/Senet".to_string(),
+            ..Repo::default()
+        };
+        let fallback = Repo {
+            name: "Senet".to_string(),
+            html_url: "https://github.com/AZUREE0/senet/".to_string(),
+            ..Repo::default()
+        };
+        assert_eq!(api.key().as_str(), "github:azuree0/senet");
+        assert_eq!(api.key().provider(), "github");
+        assert_eq!(api.key().slug(), "azuree0/senet");
+        assert_eq!(fallback.key(), api.key());
+        // Links keep the casing the key drops
+        assert_eq!(api.slug(), "azuree0/Senet");
+        assert_eq!(fallback.slug(), "AZUREE0/senet");
+    }
+
+    #[test]
+    fn same_name_different_owner_or_provider_differ() {
+        let repo = |url: &str| Repo {
+            name: "Go".to_string(),
+            html_url: url.to_string(),
+            ..Repo::default()
+        };
+        let mine = repo("https://github.com/azuree0/Go");
+        assert_ne!(mine.key(), repo("https://github.com/golang/go").key());
+        assert_ne!(mine.key(), repo("https://gitlab.com/azuree0/Go").key());
+    }
+
+    #[test]
+    fn url_without_owner_falls_back_to_name() {
+        let repo = Repo {
+            name: "Local".to_string(),
+            ..Repo::default()
+        };
+        assert_eq!(repo.key().as_str(), "github:local");
+    }
 }
@@ -0,0 +1,353 @@
+// ISO-8601 timestamps from the GitHub API, as Unix milliseconds
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// A point in time, stored as Unix milliseconds (UTC).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_unix_ms(ms: i64) -> Self {
+        Self(ms)
+    }
+
+    pub fn unix_ms(self) -> i64 {
+        self.0
+    }
+
+    /// Parses `YYYY-MM-DD`, optionally followed by `THH:MM[:SS[.fff]]` and `Z` or `±HH:MM`.
+    /// A missing offset means UTC.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (date, time) = match s.find(['T', 't', ' ']) {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let mut parts = date.splitn(3, '-');
+        let year: i64 = digits(parts.next()?)?;
+        // Four-digit years only, which also keeps the millisecond arithmetic from overflowing
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let month: u32 = digits(parts.next()?)?;
+        let day: u32 = digits(parts.next()?)?;
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        let mut ms = days_from_civil(year, month, day) * MS_PER_DAY;
+
+        if let Some(time) = time {
+            let (clock, offset_ms) = split_offset(time)?;
+            let mut fields = clock.splitn(3, ':');
+            let hour: i64 = digits(fields.next()?)?;
+            let minute: i64 = digits(fields.next()?)?;
+            let (second, millis) = match fields.next() {
+                Some(sec) => parse_seconds(sec)?,
+                None => (0, 0),
+            };
+            if hour > 23 || minute > 59 || second > 60 {
+                return None;
+            }
+            let in_day = ((hour * 60 + minute) * 60 + second) * 1000 + millis;
+            ms = ms.checked_add(in_day)?.checked_sub(offset_ms)?;
+        }
+        Some(Self(ms))
+    }
+
+    /// `YYYY-MM-DDTHH:MM:SSZ`, with milliseconds only when non-zero.
+    pub fn to_iso(self) -> String {
+        let days = self.0.div_euclid(MS_PER_DAY);
+        let in_day = self.0.rem_euclid(MS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let secs = in_day / 1000;
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        match in_day % 1000 {
+            0 => format!("{year:04}-{month:02}-{day:02}T{h:02}:{m:02}:{s:02}Z"),
+            millis => format!("{year:04}-{month:02}-{day:02}T{h:02}:{m:02}:{s:02}.{millis:03}Z"),
+        }
+    }
+
+    /// How long before `now` this was, in the largest unit that reads naturally.
+    pub fn relative_to(self, now: Timestamp) -> RelativeTime {
+        RelativeTime::from_delta_ms(self.0 - now.0)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_iso())
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_iso())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp {:?}", s)))
+    }
+}
+
+/// `deserialize_with` for optional timestamps: `null`, `""` and unparseable values become
+/// `None` instead of failing the whole repo list.
+pub fn lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Timestamp>, D::Error> {
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.as_deref().and_then(Timestamp::parse))
+}
+
+/// Unit for [`RelativeTime`], named as `Intl.RelativeTimeFormat` expects.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+impl TimeUnit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+}
+
+/// A signed, rounded distance in time: negative is in the past.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RelativeTime {
+    pub value: i64,
+    pub unit: TimeUnit,
+}
+
+impl RelativeTime {
+    /// Thresholds follow the usual "45 seconds is a minute, 22 hours is a day" rounding.
+    pub fn from_delta_ms(delta_ms: i64) -> Self {
+        let secs = delta_ms.abs() / 1000;
+        let sign = delta_ms.signum();
+        let (value, unit) = match secs {
+            0..=44 => (0, TimeUnit::Second),
+            45..=2_699 => (round_div(secs, 60).max(1), TimeUnit::Minute),
+            2_700..=79_199 => (round_div(secs, 3_600).max(1), TimeUnit::Hour),
+            79_200..=2_246_399 => (round_div(secs, 86_400).max(1), TimeUnit::Day),
+            // 26 days .. ~11 months, in average (30.44 day) months
+            2_246_400..=28_927_999 => (round_div(secs, 2_629_746).max(1), TimeUnit::Month),
+            _ => (round_div(secs, 31_556_952).max(1), TimeUnit::Year),
+        };
+        Self {
+            value: sign * value,
+            unit,
+        }
+    }
+
+    /// English fallback where `Intl.RelativeTimeFormat` is missing:
+    /// "just now", "3 days ago", "in 2 hours".
+    pub fn to_english(self) -> String {
+        if self.value == 0 {
+            return "just now".to_string();
+        }
+        let n = self.value.abs();
+        let unit = self.unit.as_str();
+        let plural = if n == 1 { "" } else { "s" };
+        if self.value < 0 {
+            format!("{} {}{} ago", n, unit, plural)
+        } else {
+            format!("in {} {}{}", n, unit, plural)
+        }
+    }
+}
+
+fn round_div(n: i64, d: i64) -> i64 {
+    (n + d / 2) / d
+}
+
+/// Splits a trailing `Z` or `±HH:MM` / `±HHMM` offset off a time of day.
+fn split_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0));
+    }
+    let Some(i) = time.rfind(['+', '-']) else {
+        return Some((time, 0));
+    };
+    let (clock, offset) = (&time[..i], &time[i + 1..]);
+    let digits: String = offset.chars().filter(|c| *c != ':').collect();
+    // Checked before slicing: a multibyte character would put byte 2 mid-character
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+    let sign = if time[i..].starts_with('-') { -1 } else { 1 };
+    Some((clock, sign * (hours * 60 + minutes) * 60_000))
+}
+
+/// `SS` or `SS.fff…` into whole seconds and milliseconds.
+fn parse_seconds(s: &str) -> Option<(i64, i64)> {
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    let secs = digits(whole)?;
+    if !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let millis = format!("{:0<3}", &frac[..frac.len().min(3)]).parse().ok()?;
+    Some((secs, millis))
+}
+
+/// A field of ASCII digits only: `str::parse` alone would also take a sign.
+fn digits<T: std::str::FromStr>(s: &str) -> Option<T> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Timestamp {
+        Timestamp::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_github_format() {
+        assert_eq!(ts("1970-01-01T00:00:00Z").unix_ms(), 0);
+        assert_eq!(ts("2025-01-10T12:00:00Z").unix_ms(), 1_736_510_400_000);
+        assert_eq!(ts("2000-02-29T23:59:59Z").unix_ms(), 951_868_799_000);
+    }
+
+    #[test]
+    fn applies_offsets_and_fractions() {
+        let utc = ts("2025-01-10T12:00:00Z");
+        assert_eq!(ts("2025-01-10T14:00:00+02:00"), utc);
+        assert_eq!(ts("2025-01-10T07:30:00-0430"), utc);
+        assert_eq!(ts("2025-01-10T12:00:00.25Z").unix_ms(), utc.unix_ms() + 250);
+        assert_eq!(ts("2025-01-10"), ts("2025-01-10T00:00:00Z"));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for bad in [
+            "",
+            "yesterday",
+            "2025-13-01",
+            "2025-02-30",
+            "2025-01-10T25:00:00Z",
+            // Multibyte offset digits and out-of-range years used to panic
+            "2025-01-10T12:00+1é2",
+            "2025-01-10T12:00-é:00",
+            "99999999999999-01-01",
+            "-99999999999999-01-01T00:00:00Z",
+            // Signed fields are not times, and used to overflow the millisecond sum
+            "2025-01-10T00:00:-9223372036854775807Z",
+            "2025-01-10T-1:-5:00Z",
+            "+2025-01-10",
+            "2025-01-10T12:60:00Z",
+            "2025-01-10T12:00:61Z",
+        ] {
+            assert_eq!(Timestamp::parse(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn iso_round_trips() {
+        for s in [
+            "1970-01-01T00:00:00Z",
+            "2025-01-10T12:00:00.250Z",
+            "1969-12-31T23:59:59Z",
+        ] {
+            assert_eq!(ts(s).to_iso(), s);
+        }
+    }
+
+    #[test]
+    fn lenient_fields_tolerate_empty_and_missing() {
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(default, deserialize_with = "lenient")]
+            at: Option<Timestamp>,
+        }
+        let at = |json: &str| serde_json::from_str::<Row>(json).unwrap().at;
+        assert_eq!(at(r#"{}"#), None);
+        assert_eq!(at(r#"{"at":null}"#), None);
+        assert_eq!(at(r#"{"at":""}"#), None);
+        assert_eq!(
+            at(r#"{"at":"1970-01-01T00:00:01Z"}"#),
+            Some(Timestamp(1_000))
+        );
+    }
+
+    #[test]
+    fn relative_picks_natural_units() {
+        let now = ts("2025-01-10T12:00:00Z");
+        let ago = |s: &str| ts(s).relative_to(now);
+        let rel = |value, unit| RelativeTime { value, unit };
+        assert_eq!(ago("2025-01-10T11:59:30Z"), rel(0, TimeUnit::Second));
+        assert_eq!(ago("2025-01-10T11:55:00Z"), rel(-5, TimeUnit::Minute));
+        assert_eq!(ago("2025-01-10T09:00:00Z"), rel(-3, TimeUnit::Hour));
+        assert_eq!(ago("2025-01-07T12:00:00Z"), rel(-3, TimeUnit::Day));
+        assert_eq!(ago("2024-11-10T12:00:00Z"), rel(-2, TimeUnit::Month));
+        assert_eq!(ago("2023-01-10T12:00:00Z"), rel(-2, TimeUnit::Year));
+        assert_eq!(ago("2025-01-10T14:00:00Z"), rel(2, TimeUnit::Hour));
+    }
+
+    #[test]
+    fn english_fallback() {
+        let rel = |value, unit| RelativeTime { value, unit }.to_english();
+        assert_eq!(rel(0, TimeUnit::Second), "just now");
+        assert_eq!(rel(-1, TimeUnit::Day), "1 day ago");
+        assert_eq!(rel(-3, TimeUnit::Month), "3 months ago");
+        assert_eq!(rel(2, TimeUnit::Hour), "in 2 hours");
+    }
+}
@@ -36,6 +36,9 @@ void main() {
 }
 "#;
 
+/// Self-referencing requestAnimationFrame closure slot.
+type AnimationHandle = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
 pub struct Scene3d;
 
 impl Component for Scene3d {
@@ -104,7 +107,9 @@ fn init_and_run(canvas: &HtmlCanvasElement) -> Result<(), String> {
     let particle_count = 400;
     let mut particles: Vec<f32> = Vec::with_capacity(particle_count * 4);
     for i in 0..particle_count {
+        #[allow(clippy::excessive_precision)]
         let x = (i as f32 * 0.618033989).fract() * 2.0 - 1.0;
+        #[allow(clippy::excessive_precision)]
         let y = (i as f32 * 0.381966011).fract() * 2.0 - 1.0;
         let size = 2.0 + (i as f32 * 0.01).fract() * 4.0;
         let alpha = 0.2 + (i as f32 * 0.07).fract() * 0.4;
@@ -121,7 +126,7 @@ fn init_and_run(canvas: &HtmlCanvasElement) -> Result<(), String> {
     let canvas = canvas.clone();
     let gl = Rc::new(gl);
 
-    let animate: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None));
+    let animate: AnimationHandle = Rc::new(RefCell::new(None));
     let animate_clone = animate.clone();
     let gl_clone = gl.clone();
     let canvas_clone = canvas.clone();
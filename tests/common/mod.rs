@@ -0,0 +1,31 @@
+// Shared test harness: fixture client, in-memory store and mock clock behind one `Backend`
+use portfolio::api::clock::MockClock;
+use portfolio::api::github::Backend;
+use portfolio::api::http::FixtureClient;
+use portfolio::api::store::MemoryStore;
+
+/// Fixture client, store and clock for one test.
+pub struct Harness {
+    pub http: FixtureClient,
+    pub store: MemoryStore,
+    pub clock: MockClock,
+}
+
+impl Harness {
+    pub fn new(http: FixtureClient) -> Self {
+        Self {
+            http,
+            store: MemoryStore::new(),
+            clock: MockClock::at_ms(1_700_000_000_000),
+        }
+    }
+
+    pub fn backend(&self) -> Backend<'_> {
+        Backend {
+            http: &self.http,
+            store: &self.store,
+            cache: &self.store,
+            clock: &self.clock,
+        }
+    }
+}
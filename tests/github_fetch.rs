@@ -0,0 +1,365 @@
+// Branch coverage for `api::github::fetch_repos_using`, replaying recorded GitHub responses
+// against an in-memory store and a mock clock, so it runs under plain `cargo test`.
+mod common;
+
+use common::Harness;
+use futures::executor::block_on;
+use portfolio::api::cache::CACHE_TTL_MS;
+use portfolio::api::github::{self, Backend, FetchOptions, CACHE_KEY, REPOS_URL};
+use portfolio::api::http::{Fixture, FixtureClient, HttpResponse};
+use portfolio::api::retry::RetryPolicy;
+use portfolio::api::store::KeyValueStore;
+use portfolio::api::{DataSource, FetchError, FetchOutcome};
+use portfolio::models::Timestamp;
+
+const PAGE_1: &str = include_str!("fixtures/repos_page1.json");
+const PAGE_2: &str = include_str!("fixtures/repos_page2.json");
+const MALFORMED: &str = include_str!("fixtures/repos_malformed.json");
+const PAGE_2_URL: &str = "https://api.github.com/user/1/repos?sort=updated&per_page=100&page=2";
+
+impl Harness {
+    fn fetch(&self, options: &FetchOptions) -> Result<FetchOutcome, FetchError> {
+        block_on(github::fetch_repos_using(self.backend(), options, None))
+    }
+
+    /// Populates the cache from `PAGE_1` through a throwaway client.
+    fn prime_cache(&self) {
+        let client = FixtureClient::new().on(REPOS_URL, ok_page(PAGE_1));
+        let backend = Backend {
+            http: &client,
+            ..self.backend()
+        };
+        block_on(github::fetch_repos_using(backend, &options(), None)).expect("priming fetch");
+    }
+
+    /// Populates the cache from `PAGE_1` linking to `PAGE_2`, tagged `"v1"` and `"v2"`.
+    fn prime_two_pages(&self) {
+        let link = format!(
+            "<{}>; rel=\"next\", <{}>; rel=\"last\"",
+            PAGE_2_URL, PAGE_2_URL
+        );
+        let first = HttpResponse::new(200, PAGE_1)
+            .with_header("ETag", "\"v1\"")
+            .with_header("Link", &link);
+        let second = HttpResponse::new(200, PAGE_2).with_header("ETag", "\"v2\"");
+        let client = FixtureClient::new()
+            .on(REPOS_URL, Fixture::Response(first))
+            .on(PAGE_2_URL, Fixture::Response(second));
+        let backend = Backend {
+            http: &client,
+            ..self.backend()
+        };
+        block_on(github::fetch_repos_using(backend, &options(), None)).expect("priming fetch");
+    }
+
+    fn cached_len(&self) -> Option<usize> {
+        let json = self.store.get(CACHE_KEY)?;
+        let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+        value["repos"].as_array().map(Vec::len)
+    }
+}
+
+fn options() -> FetchOptions {
+    FetchOptions {
+        retry: RetryPolicy::none(),
+        ..FetchOptions::default()
+    }
+}
+
+fn bypass() -> FetchOptions {
+    FetchOptions {
+        bypass_ttl: true,
+        ..options()
+    }
+}
+
+fn ok_page(body: &str) -> Fixture {
+    Fixture::Response(HttpResponse::new(200, body).with_header("ETag", "\"v1\""))
+}
+
+fn linked_page(body: &str, next: &str) -> Fixture {
+    let link = format!("<{}>; rel=\"next\", <{}>; rel=\"last\"", next, next);
+    Fixture::Response(HttpResponse::new(200, body).with_header("Link", &link))
+}
+
+#[test]
+fn fresh_fetch_writes_cache() {
+    let h = Harness::new(FixtureClient::new().on(REPOS_URL, ok_page(PAGE_1)));
+    let outcome = h.fetch(&options()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Fresh);
+    let names: Vec<_> = outcome.repos.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Senet", "Mesh"]);
+    assert_eq!(h.cached_len(), Some(2));
+}
+
+#[test]
+fn decodes_extended_repo_fields() {
+    let h = Harness::new(FixtureClient::new().on(REPOS_URL, ok_page(PAGE_1)));
+    let repos = h.fetch(&options()).unwrap().repos;
+
+    let senet = &repos[0];
+    assert_eq!((senet.id, senet.full_name.as_str()), (901, "azuree0/Senet"));
+    assert_eq!(senet.topics, ["board-game", "egypt", "wasm"]);
+    assert_eq!((senet.forks_count, senet.open_issues_count), (2, 1));
+    assert_eq!(senet.license.as_ref().map(|l| l.label()), Some("MIT"));
+    assert_eq!(senet.demo_url(), Some("https://azuree0.github.io/Senet"));
+    assert_eq!(senet.pushed_at, Timestamp::parse("2025-01-10T11:58:00Z"));
+    assert_eq!(
+        senet.updated_at.map(Timestamp::unix_ms),
+        Some(1_736_510_400_000)
+    );
+
+    let mesh = &repos[1];
+    assert!(mesh.archived && mesh.fork);
+    assert_eq!(mesh.license, None);
+    assert_eq!(mesh.demo_url(), None);
+}
+
+#[test]
+fn cache_within_ttl_skips_network() {
+    let h = Harness::new(FixtureClient::new());
+    h.prime_cache();
+    let outcome = h.fetch(&options()).unwrap();
+
+    assert!(matches!(outcome.source, DataSource::Cached { .. }));
+    assert!(h.http.requests().is_empty());
+}
+
+#[test]
+fn expired_cache_goes_to_network() {
+    let h = Harness::new(FixtureClient::new().on_json(REPOS_URL, 304, ""));
+    h.prime_cache();
+    h.clock.advance_ms(CACHE_TTL_MS + 1);
+    let outcome = h.fetch(&options()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Revalidated);
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+#[test]
+fn follows_link_header_pagination() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(REPOS_URL, linked_page(PAGE_1, PAGE_2_URL))
+            .on_json(PAGE_2_URL, 200, PAGE_2),
+    );
+    let outcome = h.fetch(&options()).unwrap();
+
+    assert_eq!(outcome.repos.len(), 3);
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn multi_page_lists_revalidate_every_page() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(REPOS_URL, 304, "")
+            .on_json(PAGE_2_URL, 304, ""),
+    );
+    h.prime_two_pages();
+    let outcome = h.fetch(&bypass()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Revalidated);
+    assert_eq!(outcome.repos.len(), 3);
+    let requests = h.http.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header_value("If-None-Match"), Some("\"v1\""));
+    assert_eq!(requests[1].header_value("If-None-Match"), Some("\"v2\""));
+}
+
+#[test]
+fn a_changed_later_page_makes_the_list_fresh() {
+    // A repo removed from page 2 leaves page 1, and so its ETag, unchanged
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(REPOS_URL, 304, "")
+            .on_json(PAGE_2_URL, 200, "[]"),
+    );
+    h.prime_two_pages();
+    let outcome = h.fetch(&bypass()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Fresh);
+    let names: Vec<_> = outcome.repos.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Senet", "Mesh"]);
+    assert_eq!(h.cached_len(), Some(2));
+}
+
+#[test]
+fn repos_repeated_across_pages_are_dropped() {
+    // The list shifted between requests, so page 2 starts with page 1's entries again
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(REPOS_URL, linked_page(PAGE_1, PAGE_2_URL))
+            .on_json(PAGE_2_URL, 200, PAGE_1),
+    );
+    let outcome = h.fetch(&options()).unwrap();
+
+    let names: Vec<_> = outcome.repos.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["Senet", "Mesh"]);
+}
+
+#[test]
+fn limit_truncates_and_stops_paginating() {
+    let h = Harness::new(FixtureClient::new().on(REPOS_URL, linked_page(PAGE_1, PAGE_2_URL)));
+    let limited = FetchOptions {
+        limit: 1,
+        ..options()
+    };
+    let outcome = h.fetch(&limited).unwrap();
+
+    assert_eq!(outcome.repos.len(), 1);
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+#[test]
+fn not_modified_revalidates_cache() {
+    let h = Harness::new(FixtureClient::new().on_json(REPOS_URL, 304, ""));
+    h.prime_cache();
+    h.clock.advance_secs(60);
+    let outcome = h.fetch(&bypass()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Revalidated);
+    assert_eq!(outcome.repos.len(), 2);
+    let sent = &h.http.requests()[0];
+    assert_eq!(sent.header_value("If-None-Match"), Some("\"v1\""));
+
+    // Revalidation bumps `fetched_at`
+    match h.fetch(&options()).unwrap().source {
+        DataSource::Cached { fetched_at } => assert_eq!(fetched_at, 1_700_000_060_000),
+        other => panic!("expected cache hit, got {:?}", other),
+    }
+}
+
+#[test]
+fn network_error_serves_stale_cache() {
+    let h =
+        Harness::new(FixtureClient::new().on(REPOS_URL, Fixture::NetworkError("offline".into())));
+    h.prime_cache();
+    let outcome = h.fetch(&bypass()).unwrap();
+
+    assert!(matches!(
+        outcome.source,
+        DataSource::StaleCache {
+            error: FetchError::Network(_),
+            ..
+        }
+    ));
+    assert_eq!(outcome.repos.len(), 2);
+}
+
+#[test]
+fn network_error_without_cache_fails() {
+    let h =
+        Harness::new(FixtureClient::new().on(REPOS_URL, Fixture::NetworkError("offline".into())));
+    assert!(matches!(h.fetch(&options()), Err(FetchError::Network(_))));
+}
+
+#[test]
+fn timeout_is_a_network_error() {
+    let h = Harness::new(FixtureClient::new().on(REPOS_URL, Fixture::Timeout));
+    assert!(matches!(h.fetch(&options()), Err(FetchError::Network(_))));
+}
+
+#[test]
+fn server_error_reports_status() {
+    let h = Harness::new(FixtureClient::new().on_json(REPOS_URL, 502, ""));
+    assert_eq!(h.fetch(&options()), Err(FetchError::Http { status: 502 }));
+}
+
+#[test]
+fn transient_error_is_retried() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(REPOS_URL, 503, "")
+            .on(REPOS_URL, ok_page(PAGE_1)),
+    );
+    let outcome = h
+        .fetch(&FetchOptions {
+            retry: RetryPolicy::default(),
+            ..options()
+        })
+        .unwrap();
+
+    assert_eq!(outcome.source, DataSource::Fresh);
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn exhausted_quota_blocks_later_fetches() {
+    let reset = (1_700_000_000 + 3_600).to_string();
+    let h = Harness::new(
+        FixtureClient::new().on(
+            REPOS_URL,
+            Fixture::Response(
+                HttpResponse::new(403, "{}")
+                    .with_header("X-RateLimit-Remaining", "0")
+                    .with_header("X-RateLimit-Reset", &reset),
+            ),
+        ),
+    );
+    assert!(matches!(
+        h.fetch(&options()),
+        Err(FetchError::RateLimited { .. })
+    ));
+    assert!(matches!(
+        h.fetch(&options()),
+        Err(FetchError::RateLimited { .. })
+    ));
+    assert_eq!(h.http.requests().len(), 1);
+
+    h.clock.advance_secs(3_600);
+    let _ = h.fetch(&options());
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn malformed_body_reports_path() {
+    let h = Harness::new(FixtureClient::new().on_json(REPOS_URL, 200, MALFORMED));
+    match h.fetch(&options()) {
+        Err(FetchError::Decode { path, .. }) => assert_eq!(path, "[0].stargazers_count"),
+        other => panic!("expected decode error, got {:?}", other),
+    }
+}
+
+#[test]
+fn failed_second_page_does_not_cache_partial_results() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(REPOS_URL, linked_page(PAGE_2, PAGE_2_URL))
+            .on_json(PAGE_2_URL, 500, ""),
+    );
+    h.prime_cache();
+    let outcome = h.fetch(&bypass()).unwrap();
+
+    assert!(matches!(outcome.source, DataSource::StaleCache { .. }));
+    assert_eq!(h.cached_len(), Some(2));
+}
+
+#[test]
+fn corrupt_cache_is_reported_and_replaced() {
+    let h = Harness::new(FixtureClient::new().on(REPOS_URL, ok_page(PAGE_1)));
+    h.store.set(CACHE_KEY, "{not json");
+    let outcome = h.fetch(&options()).unwrap();
+
+    assert_eq!(outcome.source, DataSource::Fresh);
+    assert!(matches!(
+        outcome.cache_error,
+        Some(FetchError::CacheCorrupt(_))
+    ));
+    assert_eq!(h.cached_len(), Some(2));
+    assert_eq!(h.fetch(&options()).unwrap().cache_error, None);
+}
+
+#[test]
+fn manual_refresh_is_not_covered_by_a_scheduled_one() {
+    // A scheduled refresh joins a manual one in flight; a manual one supersedes it
+    assert!(bypass().covers(&options()));
+    assert!(options().covers(&options()));
+    assert!(!options().covers(&bypass()));
+    let capped = FetchOptions {
+        limit: 10,
+        ..bypass()
+    };
+    assert!(!capped.covers(&options()));
+}
@@ -0,0 +1,550 @@
+// Per-repo detail lookups (`api::details` and the endpoints built on it), replayed against
+// fixtures with an in-memory store and a mock clock.
+mod common;
+
+use common::Harness;
+use futures::executor::block_on;
+use portfolio::api::clock::Clock;
+use portfolio::api::commit_activity::{self, PollPolicy, COMMIT_ACTIVITY};
+use portfolio::api::events::{self, ACTIVITY};
+use portfolio::api::http::{Fixture, FixtureClient, HttpResponse};
+use portfolio::api::languages::{self, LANGUAGES};
+use portfolio::api::rate_limit::OPTIONAL_RESERVE;
+use portfolio::api::readme::{self, README};
+use portfolio::api::releases::{self, RELEASE};
+use portfolio::api::screenshots::{self, SCREENSHOT};
+use portfolio::api::FetchError;
+use portfolio::markdown::{Block, Inline};
+use portfolio::models::activity::day_number;
+use portfolio::models::{RepoKey, Timestamp};
+
+const COMMIT_ACTIVITY_SENET: &str = include_str!("fixtures/commit_activity_senet.json");
+const EVENTS_PAGE_1: &str = include_str!("fixtures/events_page1.json");
+const EVENTS_PAGE_2: &str = include_str!("fixtures/events_page2.json");
+const LANGUAGES_SENET: &str = include_str!("fixtures/languages_senet.json");
+const README_SENET: &str = include_str!("fixtures/readme_senet.json");
+const README_SENET_PLAIN: &str = include_str!("fixtures/readme_senet_plain.json");
+const RELEASE_SENET: &str = include_str!("fixtures/release_senet.json");
+
+const SENET: &str = "azuree0/Senet";
+
+fn senet() -> RepoKey {
+    RepoKey::new("github", SENET)
+}
+
+fn ok(body: &str, remaining: u32) -> Fixture {
+    Fixture::Response(
+        HttpResponse::new(200, body)
+            .with_header("X-RateLimit-Remaining", &remaining.to_string())
+            .with_header("X-RateLimit-Reset", "1700003600"),
+    )
+}
+
+#[test]
+fn languages_are_fetched_then_cached() {
+    let url = languages::languages_url(&senet());
+    let h = Harness::new(FixtureClient::new().on(&url, ok(LANGUAGES_SENET, 50)));
+
+    let langs = block_on(languages::fetch_languages_using(h.backend(), &senet())).unwrap();
+    let names: Vec<_> = langs.shares(0.0).into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["Rust", "JavaScript", "GLSL", "HTML"]);
+
+    h.clock.advance_ms(LANGUAGES.ttl_ms);
+    let again = block_on(languages::fetch_languages_using(h.backend(), &senet())).unwrap();
+    assert_eq!(again, langs);
+    assert_eq!(h.http.requests().len(), 1);
+    assert_eq!(
+        h.http.requests()[0].url,
+        "https://api.github.com/repos/azuree0/senet/languages"
+    );
+}
+
+#[test]
+fn expired_entry_is_served_when_refetch_fails() {
+    let url = languages::languages_url(&senet());
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(&url, ok(LANGUAGES_SENET, 50))
+            .on_json(&url, 500, ""),
+    );
+    let langs = block_on(languages::fetch_languages_using(h.backend(), &senet())).unwrap();
+
+    h.clock.advance_ms(LANGUAGES.ttl_ms + 1);
+    let stale = block_on(languages::fetch_languages_using(h.backend(), &senet()));
+    assert_eq!(stale, Ok(langs));
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn low_quota_is_left_for_the_repo_list() {
+    let url = languages::languages_url(&senet());
+    let other = RepoKey::new("github", "azuree0/Mesh");
+    let h = Harness::new(FixtureClient::new().on(&url, ok(LANGUAGES_SENET, OPTIONAL_RESERVE)));
+
+    assert!(block_on(languages::fetch_languages_using(h.backend(), &senet())).is_ok());
+    let held_back = block_on(languages::fetch_languages_using(h.backend(), &other));
+    assert!(matches!(held_back, Err(FetchError::RateLimited { .. })));
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+/// Every link and image URL in the document, in order.
+fn urls(blocks: &[Block]) -> Vec<String> {
+    fn walk(inlines: &[Inline], out: &mut Vec<String>) {
+        for inline in inlines {
+            match inline {
+                Inline::Link { href, content, .. } => {
+                    out.push(href.clone());
+                    walk(content, out);
+                }
+                Inline::Image { src, .. } => out.push(src.clone()),
+                _ => {}
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Heading { content, .. } | Block::Paragraph(content) => walk(content, &mut out),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[test]
+fn readme_is_decoded_parsed_and_rewritten() {
+    let url = readme::readme_url(&senet());
+    let h = Harness::new(FixtureClient::new().on(&url, ok(README_SENET, 50)));
+
+    let readme = block_on(readme::fetch_readme_using(h.backend(), &senet()))
+        .unwrap()
+        .expect("Senet has a README");
+    assert_eq!(
+        readme.html_url,
+        "https://github.com/azuree0/Senet/blob/main/README.md"
+    );
+    let blocks = &readme.document.blocks;
+    assert!(matches!(blocks[0], Block::Heading { level: 1, .. }));
+    assert!(blocks.iter().any(|b| matches!(
+        b,
+        Block::Code { lang: Some(lang), text } if lang == "sh" && text == "cargo run --release"
+    )));
+    assert_eq!(
+        urls(blocks),
+        [
+            "https://raw.githubusercontent.com/azuree0/Senet/main/docs/logo.png",
+            "https://github.com/azuree0/Senet/actions",
+            "https://github.com/azuree0/Senet/actions/workflows/ci.yml/badge.svg",
+            "https://raw.githubusercontent.com/azuree0/Senet/main/docs/board.png",
+            "https://github.com/azuree0/Senet/blob/main/docs/RULES.md",
+            "https://github.com/azuree0/Senet/blob/main/LICENSE",
+        ]
+    );
+}
+
+#[test]
+fn unchanged_readme_is_not_parsed_again() {
+    let url = readme::readme_url(&senet());
+    // Same blob SHA, different body: only a reparse would notice
+    let same_sha = README_SENET.replace("PGgx", "IyBY");
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(&url, ok(README_SENET, 50))
+            .on(&url, ok(&same_sha, 49)),
+    );
+    let first = block_on(readme::fetch_readme_using(h.backend(), &senet())).unwrap();
+
+    h.clock.advance_ms(README.ttl_ms);
+    let cached = block_on(readme::fetch_readme_using(h.backend(), &senet())).unwrap();
+    assert_eq!(cached, first);
+    assert_eq!(h.http.requests().len(), 1);
+
+    h.clock.advance_ms(1);
+    let refreshed = block_on(readme::fetch_readme_using(h.backend(), &senet())).unwrap();
+    assert_eq!(refreshed, first);
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn missing_readme_is_remembered() {
+    let url = readme::readme_url(&senet());
+    let h = Harness::new(FixtureClient::new().on_json(&url, 404, r#"{"message":"Not Found"}"#));
+
+    assert_eq!(
+        block_on(readme::fetch_readme_using(h.backend(), &senet())),
+        Ok(None)
+    );
+    assert_eq!(
+        block_on(readme::fetch_readme_using(h.backend(), &senet())),
+        Ok(None)
+    );
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+fn no_readme(http: FixtureClient) -> FixtureClient {
+    http.on_json(
+        &readme::readme_url(&senet()),
+        404,
+        r#"{"message":"Not Found"}"#,
+    )
+}
+
+#[test]
+fn first_readme_image_past_the_badges_is_the_screenshot() {
+    let url = readme::readme_url(&senet());
+    let h = Harness::new(FixtureClient::new().on(&url, ok(README_SENET, 50)));
+
+    let shot = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(
+        shot.as_ref().map(Option::as_deref),
+        Ok(Some(
+            "https://raw.githubusercontent.com/azuree0/Senet/main/docs/logo.png"
+        ))
+    );
+
+    h.clock.advance_ms(SCREENSHOT.ttl_ms);
+    let again = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(again, shot);
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+#[test]
+fn docs_preview_is_found_by_convention() {
+    let root = r#"[
+        {"name": "README.md", "type": "file", "download_url": "https://raw/README.md"},
+        {"name": "screenshot.svg", "type": "file", "download_url": "https://raw/screenshot.svg"},
+        {"name": "docs", "type": "dir", "download_url": null}
+    ]"#;
+    let docs = r#"[
+        {"name": "RULES.md", "type": "file", "download_url": "https://raw/docs/RULES.md"},
+        {"name": "Preview.webp", "type": "file", "download_url": "https://raw/docs/Preview.webp"}
+    ]"#;
+    let h = Harness::new(no_readme(
+        FixtureClient::new()
+            .on(&screenshots::contents_url(&senet(), ""), ok(root, 50))
+            .on(&screenshots::contents_url(&senet(), "docs"), ok(docs, 49)),
+    ));
+
+    let shot = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(
+        shot.as_ref().map(Option::as_deref),
+        Ok(Some("https://raw/docs/Preview.webp"))
+    );
+    assert_eq!(h.http.requests().len(), 3);
+}
+
+#[test]
+fn readme_without_images_falls_back_to_files_then_nothing() {
+    let root = r#"[
+        {"name": "README.md", "type": "file", "download_url": "https://raw/README.md"},
+        {"name": "docs", "type": "dir", "download_url": null}
+    ]"#;
+    let docs = r#"[
+        {"name": "RULES.md", "type": "file", "download_url": "https://raw/docs/RULES.md"}
+    ]"#;
+    let readme = readme::readme_url(&senet());
+    let root_url = screenshots::contents_url(&senet(), "");
+    let docs_url = screenshots::contents_url(&senet(), "docs");
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(&readme, ok(README_SENET_PLAIN, 50))
+            .on(&root_url, ok(root, 49))
+            .on(&docs_url, ok(docs, 48)),
+    );
+
+    let shot = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(shot, Ok(None));
+    let asked: Vec<_> = h.http.requests().into_iter().map(|r| r.url).collect();
+    assert_eq!(asked, [readme, root_url, docs_url]);
+}
+
+#[test]
+fn repo_without_an_image_has_none_and_remembers_it() {
+    let h = Harness::new(no_readme(FixtureClient::new().on_json(
+        &screenshots::contents_url(&senet(), ""),
+        404,
+        r#"{"message":"This repository is empty."}"#,
+    )));
+
+    let shot = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(shot, Ok(None));
+    let again = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(again, Ok(None));
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn failed_discovery_is_retried_rather_than_cached() {
+    let contents = screenshots::contents_url(&senet(), "");
+    let h = Harness::new(no_readme(
+        FixtureClient::new().on_json(&contents, 500, "").on_json(
+            &contents,
+            404,
+            r#"{"message":"This repository is empty."}"#,
+        ),
+    ));
+
+    let failed = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert!(failed.is_err());
+    // The missing README is remembered; only the listing is asked for again
+    let shot = block_on(screenshots::fetch_screenshot_using(h.backend(), &senet()));
+    assert_eq!(shot, Ok(None));
+    assert_eq!(h.http.requests().len(), 3);
+}
+
+#[test]
+fn latest_release_sums_asset_downloads() {
+    let url = releases::latest_release_url(&senet());
+    let h = Harness::new(FixtureClient::new().on(&url, ok(RELEASE_SENET, 50)));
+
+    let release = block_on(releases::fetch_release_using(h.backend(), &senet(), SENET))
+        .unwrap()
+        .expect("Senet has a release");
+    assert_eq!(release.tag, "v1.2.0");
+    assert_eq!(release.name.as_deref(), Some("Senet 1.2"));
+    assert_eq!(
+        release.html_url,
+        "https://github.com/azuree0/Senet/releases/tag/v1.2.0"
+    );
+    assert_eq!(
+        release.published_at,
+        Timestamp::parse("2024-03-01T09:30:00Z")
+    );
+    assert_eq!((release.assets, release.downloads), (3, 1_204));
+    assert!(!release.tag_only);
+
+    h.clock.advance_ms(RELEASE.ttl_ms);
+    let cached = block_on(releases::fetch_release_using(h.backend(), &senet(), SENET)).unwrap();
+    assert_eq!(cached, Some(release));
+    assert_eq!(h.http.requests().len(), 1);
+}
+
+#[test]
+fn newest_tag_stands_in_without_a_release() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(
+                &releases::latest_release_url(&senet()),
+                404,
+                r#"{"message":"Not Found"}"#,
+            )
+            .on(
+                &releases::tags_url(&senet()),
+                ok(r#"[{"name": "v0.3.1", "commit": {"sha": "9c1e"}}]"#, 49),
+            ),
+    );
+
+    let release = block_on(releases::fetch_release_using(h.backend(), &senet(), SENET))
+        .unwrap()
+        .expect("Senet has a tag");
+    assert_eq!(release.tag, "v0.3.1");
+    assert!(release.tag_only);
+    assert_eq!(
+        release.html_url,
+        "https://github.com/azuree0/Senet/releases/tag/v0.3.1"
+    );
+    assert_eq!((release.assets, release.downloads), (0, 0));
+}
+
+#[test]
+fn highest_version_tag_wins_over_name_order() {
+    let tags = r#"[
+        {"name": "v0.9.0"},
+        {"name": "v0.3.1"},
+        {"name": "v0.10.0-rc.1"},
+        {"name": "v0.10.0"},
+        {"name": "nightly"}
+    ]"#;
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(
+                &releases::latest_release_url(&senet()),
+                404,
+                r#"{"message":"Not Found"}"#,
+            )
+            .on(&releases::tags_url(&senet()), ok(tags, 49)),
+    );
+
+    let release = block_on(releases::fetch_release_using(h.backend(), &senet(), SENET))
+        .unwrap()
+        .expect("Senet has tags");
+    assert_eq!(release.tag, "v0.10.0");
+}
+
+#[test]
+fn tag_page_link_is_percent_encoded() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(
+                &releases::latest_release_url(&senet()),
+                404,
+                r#"{"message":"Not Found"}"#,
+            )
+            .on(
+                &releases::tags_url(&senet()),
+                ok(r#"[{"name": "v1.0+build/2 #1"}]"#, 49),
+            ),
+    );
+
+    let release = block_on(releases::fetch_release_using(h.backend(), &senet(), SENET))
+        .unwrap()
+        .expect("Senet has a tag");
+    assert_eq!(release.tag, "v1.0+build/2 #1");
+    assert_eq!(
+        release.html_url,
+        "https://github.com/azuree0/Senet/releases/tag/v1.0%2Bbuild%2F2%20%231"
+    );
+}
+
+#[test]
+fn untagged_repo_has_no_version() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on_json(
+                &releases::latest_release_url(&senet()),
+                404,
+                r#"{"message":"Not Found"}"#,
+            )
+            .on(&releases::tags_url(&senet()), ok("[]", 49)),
+    );
+
+    assert_eq!(
+        block_on(releases::fetch_release_using(h.backend(), &senet(), SENET)),
+        Ok(None)
+    );
+    assert_eq!(
+        block_on(releases::fetch_release_using(h.backend(), &senet(), SENET)),
+        Ok(None)
+    );
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+fn computing() -> Fixture {
+    Fixture::Response(HttpResponse::new(202, "{}").with_header("X-RateLimit-Remaining", "48"))
+}
+
+#[test]
+fn stats_are_polled_until_github_has_computed_them() {
+    let url = commit_activity::commit_activity_url(&senet());
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(&url, computing())
+            .on(&url, computing())
+            .on(&url, ok(COMMIT_ACTIVITY_SENET, 47)),
+    );
+    let start = h.clock.now_ms();
+
+    let policy = PollPolicy::default();
+    let activity = block_on(commit_activity::fetch_commit_activity_using(
+        h.backend(),
+        &senet(),
+        &policy,
+    ))
+    .unwrap();
+    assert_eq!(activity.weekly.len(), 52);
+    assert_eq!(activity.total(), 154);
+    assert_eq!(activity.first_week, Timestamp::parse("2023-09-17"));
+    assert_eq!(h.http.requests().len(), 3);
+    assert_eq!(h.clock.now_ms() - start, 2_000 + 4_000);
+
+    let cached = block_on(commit_activity::fetch_commit_activity_using(
+        h.backend(),
+        &senet(),
+        &policy,
+    ));
+    assert_eq!(cached, Ok(activity));
+    assert_eq!(h.http.requests().len(), 3);
+}
+
+#[test]
+fn stats_still_computing_are_asked_for_again_later() {
+    let url = commit_activity::commit_activity_url(&senet());
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(&url, computing())
+            .on(&url, computing())
+            .on(&url, ok(COMMIT_ACTIVITY_SENET, 47)),
+    );
+    let policy = PollPolicy {
+        max_polls: 2,
+        ..PollPolicy::default()
+    };
+
+    let pending = block_on(commit_activity::fetch_commit_activity_using(
+        h.backend(),
+        &senet(),
+        &policy,
+    ));
+    assert_eq!(pending, Err(FetchError::Http { status: 202 }));
+    assert_eq!(h.http.requests().len(), 2);
+
+    h.clock.advance_ms(COMMIT_ACTIVITY.ttl_ms / 2);
+    let ready = block_on(commit_activity::fetch_commit_activity_using(
+        h.backend(),
+        &senet(),
+        &policy,
+    ));
+    assert_eq!(ready.map(|a| a.total()), Ok(154));
+}
+
+#[test]
+fn empty_repo_has_quiet_stats() {
+    let url = commit_activity::commit_activity_url(&senet());
+    let h =
+        Harness::new(FixtureClient::new().on(&url, Fixture::Response(HttpResponse::new(204, ""))));
+
+    let activity = block_on(commit_activity::fetch_commit_activity_using(
+        h.backend(),
+        &senet(),
+        &PollPolicy::default(),
+    ))
+    .unwrap();
+    assert!(activity.is_quiet());
+    assert_eq!(activity.first_week, None);
+}
+
+const EVENTS_PAGE_2_URL: &str =
+    "https://api.github.com/user/1001/events/public?per_page=100&page=2";
+
+fn linked(body: &str, next: &str) -> Fixture {
+    let link = format!("<{}>; rel=\"next\", <{}>; rel=\"last\"", next, next);
+    Fixture::Response(HttpResponse::new(200, body).with_header("Link", &link))
+}
+
+fn day(date: &str) -> i64 {
+    day_number(Timestamp::parse(date).unwrap())
+}
+
+#[test]
+fn events_are_paged_and_bucketed_by_day() {
+    let h = Harness::new(
+        FixtureClient::new()
+            .on(events::EVENTS_URL, linked(EVENTS_PAGE_1, EVENTS_PAGE_2_URL))
+            .on(EVENTS_PAGE_2_URL, ok(EVENTS_PAGE_2, 48)),
+    );
+
+    let activity = block_on(events::fetch_activity_using(h.backend())).unwrap();
+    assert_eq!(h.http.requests().len(), 2);
+    let busy = activity.day(day("2023-11-14"));
+    assert_eq!((busy.pushes, busy.pull_requests), (2, 1));
+    assert_eq!(activity.day(day("2023-11-10")).issues, 1);
+    let release_day = activity.day(day("2023-10-01"));
+    assert_eq!((release_day.releases, release_day.other), (1, 1));
+    // More than a year before the mock clock's 2023-11-14
+    assert_eq!(activity.day(day("2022-10-01")).total(), 0);
+    assert_eq!((activity.total(), activity.busiest()), (6, 3));
+
+    h.clock.advance_ms(ACTIVITY.ttl_ms);
+    let cached = block_on(events::fetch_activity_using(h.backend()));
+    assert_eq!(cached, Ok(activity));
+    assert_eq!(h.http.requests().len(), 2);
+}
+
+#[test]
+fn events_stop_at_the_last_page() {
+    let h = Harness::new(FixtureClient::new().on(events::EVENTS_URL, ok(EVENTS_PAGE_2, 50)));
+
+    let activity = block_on(events::fetch_activity_using(h.backend())).unwrap();
+    assert_eq!(activity.total(), 2);
+    assert_eq!(h.http.requests().len(), 1);
+}